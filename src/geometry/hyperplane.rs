@@ -0,0 +1,325 @@
+use geng::prelude::*;
+
+use super::{mat_n::MatN, vec_n::VecN};
+
+/// Hyperplane in the N-dimensional space: points at the distance `offset` along the `normal`.
+#[derive(Debug, Clone, Copy)]
+pub struct Hyperplane<const N: usize> {
+    /// Unit normal, use [Hyperplane::new] to normalize an arbitrary one.
+    pub normal: VecN<N>,
+    pub offset: f32,
+}
+
+/// Identifies a point of a cross section, so that all simplices sharing it agree on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionPoint {
+    /// A vertex of the mesh lying exactly on the hyperplane.
+    Vertex(usize),
+    /// A crossing in the middle of the edge, from the vertex below the hyperplane
+    /// to the one above it.
+    Edge([usize; 2]),
+}
+
+/// Whether the point at the signed `distance` from a hyperplane is below it.
+///
+/// Points exactly on the hyperplane count as above it, as if the hyperplane
+/// was moved by an infinitely small distance down. This is the only predicate used
+/// for slicing, so vertices, edges and faces lying on the hyperplane are classified
+/// the same way by every simplex containing them, and the sections stay closed.
+pub fn is_below(distance: f32) -> bool {
+    distance < 0.0
+}
+
+/// Where the edge between the vertices `a` and `b` at the signed `distances`
+/// crosses the hyperplane, as the point key and the interpolation factor from `a` to `b`.
+/// Returns `None` if both vertices are on the same side according to [is_below].
+pub fn edge_crossing([a, b]: [usize; 2], [da, db]: [f32; 2]) -> Option<(SectionPoint, f32)> {
+    match (is_below(da), is_below(db)) {
+        (true, false) if db == 0.0 => Some((SectionPoint::Vertex(b), 1.0)),
+        (true, false) => Some((SectionPoint::Edge([a, b]), da / (da - db))),
+        (false, true) if da == 0.0 => Some((SectionPoint::Vertex(a), 0.0)),
+        (false, true) => Some((SectionPoint::Edge([b, a]), da / (da - db))),
+        _ => None,
+    }
+}
+
+/// Mesh of simplices in the N-dimensional space, e.g. triangles or tetrahedra.
+/// All simplices have the same number of vertices.
+#[derive(Debug, Clone, Default)]
+pub struct SimplexMesh<const N: usize> {
+    pub vertices: Vec<VecN<N>>,
+    pub simplices: Vec<Vec<usize>>,
+}
+
+impl<const N: usize> Hyperplane<N> {
+    pub fn new(normal: VecN<N>, offset: f32) -> Self {
+        Self {
+            normal: normal.normalize_or_zero(),
+            offset,
+        }
+    }
+
+    /// Returns an orthonormal basis of the hyperplane: N - 1 unit vectors orthogonal
+    /// to the normal and to each other.
+    /// Together with the normal they form a frame with the same handedness as the axes.
+    pub fn basis(&self) -> Vec<VecN<N>> {
+        let normal = self.normal;
+
+        // Drop the axis closest to the normal, the rest are guaranteed to be independent from it
+        let skip = (0..N).max_by_key(|&i| r32(normal[i].abs())).unwrap_or(0);
+
+        let mut basis: Vec<VecN<N>> = Vec::with_capacity(N - 1);
+        for axis in (0..N).filter(|&i| i != skip).map(VecN::unit) {
+            // Gram-Schmidt
+            let mut v = axis - normal * VecN::dot(axis, normal);
+            for &b in &basis {
+                v -= b * VecN::dot(v, b);
+            }
+            basis.push(v.normalize_or_zero());
+        }
+
+        let frame: [VecN<N>; N] = std::array::from_fn(|i| basis.get(i).copied().unwrap_or(normal));
+        if N > 1 && MatN::from_rows(frame).determinant() < 0.0 {
+            basis[N - 2] = -basis[N - 2];
+        }
+        basis
+    }
+
+    pub fn distance(&self, point: VecN<N>) -> f32 {
+        VecN::dot(self.normal, point) - self.offset
+    }
+
+    pub fn project(&self, point: VecN<N>) -> VecN<N> {
+        point - self.normal * self.distance(point)
+    }
+
+    /// Returns a function giving the coordinates of the projection of a point
+    /// along the [basis](Self::basis), so that the basis is only computed once.
+    /// `M` must be equal to `N - 1`.
+    pub fn local_projection<const M: usize>(&self) -> impl Fn(VecN<N>) -> VecN<M> {
+        assert_eq!(M + 1, N, "the hyperplane has {} dimensions", N - 1);
+        let basis = self.basis();
+        let origin = self.normal * self.offset;
+        move |point| {
+            let delta = point - origin;
+            VecN(std::array::from_fn(|i| VecN::dot(basis[i], delta)))
+        }
+    }
+
+    /// Inverse of [Self::local_projection]: returns the point with the given local coordinates
+    /// at the given `distance` from the hyperplane.
+    #[cfg(test)]
+    pub fn unproject_local<const M: usize>(&self, point: VecN<M>, distance: f32) -> VecN<N> {
+        assert_eq!(M + 1, N, "the hyperplane has {} dimensions", N - 1);
+        self.basis()
+            .into_iter()
+            .zip(point.0)
+            .fold(self.normal * (self.offset + distance), |acc, (b, x)| {
+                acc + b * x
+            })
+    }
+
+    /// Calculate a cross section of the mesh with the hyperplane.
+    /// A k-simplex is sliced into (k - 1)-simplices, so a mesh of triangles becomes
+    /// a set of segments, tetrahedra become triangles, and so on.
+    ///
+    /// Every edge of the mesh is intersected once, so the resulting simplices share
+    /// their vertices, and slicing a closed surface gives a closed surface.
+    /// Vertices lying on the hyperplane are classified by [is_below], and the simplices
+    /// collapsed by them are skipped.
+    /// The simplices are not oriented.
+    pub fn slice(&self, mesh: &SimplexMesh<N>) -> SimplexMesh<N> {
+        let simplices = mesh.simplices.iter().map(Vec::as_slice);
+        self.slice_simplices(&mesh.vertices, simplices).0
+    }
+
+    /// Same as [Self::slice] for the simplices given as indices into the `vertices`.
+    /// Also returns the index of the simplex every simplex of the section comes from.
+    pub fn slice_simplices<'a>(
+        &self,
+        vertices: &[VecN<N>],
+        simplices: impl IntoIterator<Item = &'a [usize]>,
+    ) -> (SimplexMesh<N>, Vec<usize>) {
+        let distances: Vec<f32> = vertices.iter().map(|&v| self.distance(v)).collect();
+
+        let mut section = SimplexMesh::default();
+        let mut sources = Vec::new();
+        let mut section_points: HashMap<SectionPoint, usize> = HashMap::new();
+        let mut paths: HashMap<(usize, usize), Vec<LatticePath>> = HashMap::new();
+        for (source, simplex) in simplices.into_iter().enumerate() {
+            let (below, above): (Vec<usize>, Vec<usize>) =
+                simplex.iter().partition(|&&v| is_below(distances[v]));
+            if below.is_empty() || above.is_empty() {
+                continue;
+            }
+
+            let mut edge_point = |a: usize, b: usize| {
+                let (key, t) = edge_crossing([a, b], [distances[a], distances[b]])
+                    .expect("the edge must cross the hyperplane");
+                *section_points.entry(key).or_insert_with(|| {
+                    let (a, b) = (vertices[a], vertices[b]);
+                    section.vertices.push(a + (b - a) * t);
+                    section.vertices.len() - 1
+                })
+            };
+
+            // The section is a product of two simplices with the vertices at the crossed edges.
+            // Split it with the staircase triangulation: every monotone path
+            // through the grid of the edges gives one simplex.
+            let paths = paths
+                .entry((below.len(), above.len()))
+                .or_insert_with(|| staircase_paths(below.len(), above.len()));
+            for path in paths.iter() {
+                let simplex: Vec<usize> = path
+                    .iter()
+                    .map(|&(i, j)| edge_point(below[i], above[j]))
+                    .collect();
+                if (1..simplex.len()).all(|i| !simplex[..i].contains(&simplex[i])) {
+                    section.simplices.push(simplex);
+                    sources.push(source);
+                }
+            }
+        }
+
+        (section, sources)
+    }
+}
+
+/// Merges the overlapping or touching intervals `[min, max]` on a line,
+/// e.g. the segments of a 1d section, into disjoint ones sorted from left to right.
+pub fn merge_intervals(mut intervals: Vec<[f32; 2]>) -> Vec<[f32; 2]> {
+    intervals.sort_by(|a, b| a[0].total_cmp(&b[0]));
+    let mut merged: Vec<[f32; 2]> = Vec::with_capacity(intervals.len());
+    for [min, max] in intervals {
+        match merged.last_mut() {
+            Some(last) if min <= last[1] => last[1] = last[1].max(max),
+            _ => merged.push([min, max]),
+        }
+    }
+    merged
+}
+
+/// Sequence of the points of a grid, every step increments one of the coordinates.
+type LatticePath = Vec<(usize, usize)>;
+
+/// All monotone lattice paths from `(0, 0)` to `(n - 1, m - 1)`.
+fn staircase_paths(n: usize, m: usize) -> Vec<LatticePath> {
+    let mut paths = Vec::new();
+    let mut stack = vec![vec![(0, 0)]];
+    while let Some(path) = stack.pop() {
+        let (i, j) = *path.last().unwrap();
+        if i + 1 == n && j + 1 == m {
+            paths.push(path);
+            continue;
+        }
+        for (di, dj) in [(1, 0), (0, 1)] {
+            if i + di < n && j + dj < m {
+                let mut next = path.clone();
+                next.push((i + di, j + dj));
+                stack.push(next);
+            }
+        }
+    }
+    paths
+}
+
+#[test]
+fn test_merge_intervals() {
+    assert!(merge_intervals(Vec::new()).is_empty());
+    assert_eq!(
+        merge_intervals(vec![
+            [2.0, 3.0],
+            [0.0, 1.0],
+            [0.5, 0.7],
+            [1.0, 1.5],
+            [2.5, 4.0]
+        ]),
+        vec![[0.0, 1.5], [2.0, 4.0]]
+    );
+
+    // A fan of triangles is sliced into the segments touching each other
+    let hexagon = super::shape::unit_polygon_filled(6);
+    let line = Hyperplane {
+        normal: VecN::unit(1),
+        offset: 0.1,
+    };
+    let section = line.slice(&hexagon);
+    let project = line.local_projection::<1>();
+    let segments = section
+        .simplices
+        .iter()
+        .map(|segment| {
+            let [a, b] = [segment[0], segment[1]].map(|v| project(section.vertices[v])[0]);
+            [a.min(b), a.max(b)]
+        })
+        .collect::<Vec<_>>();
+    assert!(segments.len() > 1);
+    assert_eq!(merge_intervals(segments).len(), 1);
+}
+
+#[test]
+fn test_slice_cube() {
+    /// Slices the Kuhn triangulation of the unit cube and checks the volume of the section.
+    fn check<const N: usize, const M: usize>() {
+        // Every permutation of the axes gives a simplex
+        let mut permutations: Vec<Vec<usize>> = vec![vec![]];
+        for _ in 0..N {
+            let mut longer = Vec::new();
+            for perm in &permutations {
+                for axis in (0..N).filter(|axis| !perm.contains(axis)) {
+                    let mut perm = perm.clone();
+                    perm.push(axis);
+                    longer.push(perm);
+                }
+            }
+            permutations = longer;
+        }
+        let index = |corner: &[bool]| corner.iter().rev().fold(0, |acc, &x| acc * 2 + x as usize);
+        let vertices = (0..1 << N)
+            .map(|id: usize| VecN(std::array::from_fn(|i| (id >> i & 1) as f32)))
+            .collect();
+        let simplices = permutations
+            .into_iter()
+            .map(|perm| {
+                let mut corner = [false; N];
+                let mut simplex = vec![index(&corner)];
+                for axis in perm {
+                    corner[axis] = true;
+                    simplex.push(index(&corner));
+                }
+                simplex
+            })
+            .collect();
+        let cube = SimplexMesh::<N> {
+            vertices,
+            simplices,
+        };
+
+        let hyperplane = Hyperplane {
+            normal: VecN::unit(0),
+            offset: 0.3,
+        };
+        let section = hyperplane.slice(&cube);
+        let project = hyperplane.local_projection::<M>();
+        let factorial: f32 = (1..=M).map(|i| i as f32).product();
+        let volume: f32 = section
+            .simplices
+            .iter()
+            .map(|simplex| {
+                assert_eq!(simplex.len(), N);
+                let points: Vec<VecN<M>> = simplex
+                    .iter()
+                    .map(|&i| project(section.vertices[i]))
+                    .collect();
+                let edges: [VecN<M>; M] = std::array::from_fn(|i| points[i + 1] - points[0]);
+                MatN::from_rows(edges).determinant().abs() / factorial
+            })
+            .sum();
+        assert!((volume - 1.0).abs() < 1e-4, "{N}d: volume {volume} != 1");
+    }
+
+    check::<2, 1>();
+    check::<3, 2>();
+    check::<4, 3>();
+    check::<5, 4>();
+}
@@ -1,11 +1,11 @@
 use geng::prelude::*;
 
-use super::vec_5::vec5;
+use super::{vec4_cross, vec4_norm, vec_5::vec5};
 
 /// 5x5 matrix
 #[allow(non_camel_case_types)]
 #[repr(C)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct mat5<T>(pub(crate) [[T; 5]; 5]);
 
 impl<T> mat5<T> {
@@ -135,70 +135,118 @@ impl<T: Num + Copy> mat5<T> {
 }
 
 impl<T: Float> mat5<T> {
-    /// Construct matrix rotating on xy plane.
-    pub fn rotate_xy(angle: Angle<T>) -> Self {
-        let mut result = Self::identity();
+    /// Construct matrix rotating in the plane spanned by `u` and `v`,
+    /// turning `u` towards `v` by the given angle.
+    /// The vectors do not need to be orthonormal, only linearly independent.
+    #[cfg(test)]
+    pub fn rotate_plane(u: vec4<T>, v: vec4<T>, angle: Angle<T>) -> Self {
+        let u = vec4_norm(u);
+        let v = vec4_norm(v - u * vec4::dot(u, v));
         let (sin, cos) = angle.sin_cos();
-        result[(1, 1)] = cos;
-        result[(1, 2)] = -sin;
-        result[(2, 1)] = sin;
-        result[(2, 2)] = cos;
-        result
-    }
-
-    /// Construct matrix rotating on xz plane.
-    pub fn rotate_xz(angle: Angle<T>) -> Self {
+        let (u, v) = ([u.x, u.y, u.z, u.w], [v.x, v.y, v.z, v.w]);
         let mut result = Self::identity();
-        let (sin, cos) = angle.sin_cos();
-        result[(0, 0)] = cos;
-        result[(2, 0)] = -sin;
-        result[(0, 2)] = sin;
-        result[(2, 2)] = cos;
+        for i in 0..4 {
+            for j in 0..4 {
+                result[(i, j)] += sin * (v[i] * u[j] - u[i] * v[j])
+                    + (cos - T::ONE) * (u[i] * u[j] + v[i] * v[j]);
+            }
+        }
         result
     }
 
-    /// Construct matrix rotating on xw plane.
-    pub fn rotate_xw(angle: Angle<T>) -> Self {
-        let mut result = Self::identity();
-        let (sin, cos) = angle.sin_cos();
-        result[(0, 0)] = cos;
-        result[(3, 0)] = -sin;
-        result[(0, 3)] = sin;
-        result[(3, 3)] = cos;
+    /// Construct a view matrix of the observer at `eye` looking at `target`.
+    /// The direction to the target becomes the w axis,
+    /// `up` and `over` are projected to be orthogonal to it and become the y and z axes,
+    /// and x is chosen to preserve the handedness.
+    pub fn look_at(eye: vec4<T>, target: vec4<T>, up: vec4<T>, over: vec4<T>) -> Self {
+        let w = vec4_norm(target - eye);
+        let y = vec4_norm(up - w * vec4::dot(up, w));
+        let z = vec4_norm(over - w * vec4::dot(over, w) - y * vec4::dot(over, y));
+        let x = -vec4_cross(y, z, w);
+        let row = |v: vec4<T>| [v.x, v.y, v.z, v.w, T::ZERO];
+        Self::new([
+            row(x),
+            row(y),
+            row(z),
+            row(w),
+            [T::ZERO, T::ZERO, T::ZERO, T::ZERO, T::ONE],
+        ]) * Self::translate(-eye)
+    }
+
+    /// Get the determinant of the matrix.
+    #[cfg(test)]
+    pub fn determinant(&self) -> T {
+        let mut m = *self;
+        let mut result = T::ONE;
+        for col in 0..5 {
+            let pivot = m.pivot_row(col);
+            if m[(pivot, col)] == T::ZERO {
+                return T::ZERO;
+            }
+            if pivot != col {
+                m.swap_rows(pivot, col);
+                result = -result;
+            }
+            result *= m[(col, col)];
+            for row in col + 1..5 {
+                let factor = m[(row, col)] / m[(col, col)];
+                for j in col..5 {
+                    let value = m[(col, j)];
+                    m[(row, j)] -= factor * value;
+                }
+            }
+        }
         result
     }
 
-    /// Construct matrix rotating on yz plane.
-    pub fn rotate_yz(angle: Angle<T>) -> Self {
+    /// Get the inverse of the matrix.
+    /// The result is undefined (contains infinities or NaNs) if the matrix is singular.
+    pub fn inverse(self) -> Self {
+        // Gauss-Jordan elimination
+        let mut m = self;
         let mut result = Self::identity();
-        let (sin, cos) = angle.sin_cos();
-        result[(1, 1)] = cos;
-        result[(2, 1)] = -sin;
-        result[(1, 2)] = sin;
-        result[(2, 2)] = cos;
+        for col in 0..5 {
+            let pivot = m.pivot_row(col);
+            m.swap_rows(pivot, col);
+            result.swap_rows(pivot, col);
+
+            let inv = T::ONE / m[(col, col)];
+            for j in 0..5 {
+                m[(col, j)] *= inv;
+                result[(col, j)] *= inv;
+            }
+            for row in 0..5 {
+                if row == col {
+                    continue;
+                }
+                let factor = m[(row, col)];
+                for j in 0..5 {
+                    let (value, inverse_value) = (m[(col, j)], result[(col, j)]);
+                    m[(row, j)] -= factor * value;
+                    result[(row, j)] -= factor * inverse_value;
+                }
+            }
+        }
         result
     }
 
-    /// Construct matrix rotating on yw plane.
-    pub fn rotate_yw(angle: Angle<T>) -> Self {
-        let mut result = Self::identity();
-        let (sin, cos) = angle.sin_cos();
-        result[(1, 1)] = cos;
-        result[(3, 1)] = -sin;
-        result[(1, 3)] = sin;
-        result[(3, 3)] = cos;
-        result
+    /// Partial pivoting: the row at or below `col` with the largest absolute value in the column.
+    fn pivot_row(&self, col: usize) -> usize {
+        let mut pivot = col;
+        for row in col + 1..5 {
+            if self[(row, col)].abs() > self[(pivot, col)].abs() {
+                pivot = row;
+            }
+        }
+        pivot
     }
 
-    /// Construct matrix rotating on zw plane.
-    pub fn rotate_zw(angle: Angle<T>) -> Self {
-        let mut result = Self::identity();
-        let (sin, cos) = angle.sin_cos();
-        result[(2, 2)] = cos;
-        result[(3, 2)] = -sin;
-        result[(2, 3)] = sin;
-        result[(3, 3)] = cos;
-        result
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for j in 0..5 {
+            let value = self[(a, j)];
+            self[(a, j)] = self[(b, j)];
+            self[(b, j)] = value;
+        }
     }
 }
 
@@ -326,3 +374,27 @@ impl<T: Num + Copy> Mul<vec5<T>> for mat5<T> {
         )
     }
 }
+
+#[test]
+fn test_mat5_inverse() {
+    let matrix = mat5::translate(vec4(1.0, -2.0, 3.0, 0.5))
+        * mat5::rotate_plane(
+            vec4(1.0, 1.0, 0.0, 0.0),
+            vec4(0.0, 1.0, 0.0, 2.0),
+            Angle::from_degrees(40.0),
+        )
+        * mat5::rotate_plane(vec4::UNIT_Z, vec4::UNIT_W, Angle::from_degrees(25.0))
+        * mat5::scale(vec4(2.0, 0.5, 1.0, 3.0));
+    assert!((matrix.determinant() - 3.0).abs() < 1e-4);
+    assert!((matrix * matrix.inverse()).approx_distance_to(&mat5::identity()) < 1e-5);
+    assert!((matrix.inverse() * matrix).approx_distance_to(&mat5::identity()) < 1e-5);
+
+    let view = mat5::look_at(
+        vec4(1.0, 2.0, 3.0, 4.0),
+        vec4(1.0, 2.0, 3.0, 10.0),
+        vec4::UNIT_Y,
+        vec4::UNIT_Z,
+    );
+    assert!(view.approx_distance_to(&mat5::translate(vec4(-1.0, -2.0, -3.0, -4.0))) < 1e-5);
+    assert!((view.determinant() - 1.0).abs() < 1e-5);
+}
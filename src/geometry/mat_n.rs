@@ -0,0 +1,115 @@
+use geng::prelude::*;
+
+use super::vec_n::VecN;
+
+/// Square matrix of an arbitrary dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatN<const N: usize>([[f32; N]; N]);
+
+impl<const N: usize> MatN<N> {
+    /// Construct a matrix with the given vectors as rows.
+    pub fn from_rows(rows: [VecN<N>; N]) -> Self {
+        Self(rows.map(|row| row.0))
+    }
+
+    /// Construct zero matrix.
+    pub fn zero() -> Self {
+        Self([[0.0; N]; N])
+    }
+
+    /// Construct identity matrix.
+    pub fn identity() -> Self {
+        let mut result = Self::zero();
+        for i in 0..N {
+            result[(i, i)] = 1.0;
+        }
+        result
+    }
+
+    /// Construct matrix rotating in the plane of the axes `a` and `b`,
+    /// turning `a` towards `b` by the given angle.
+    pub fn rotate(a: usize, b: usize, angle: Angle<f32>) -> Self {
+        let mut result = Self::identity();
+        let (sin, cos) = angle.sin_cos();
+        result[(a, a)] = cos;
+        result[(b, a)] = sin;
+        result[(a, b)] = -sin;
+        result[(b, b)] = cos;
+        result
+    }
+
+    /// Get a row as a [VecN]
+    pub fn row(&self, row_index: usize) -> VecN<N> {
+        VecN(self.0[row_index])
+    }
+
+    /// Get a column as a [VecN]
+    pub fn col(&self, col_index: usize) -> VecN<N> {
+        VecN(std::array::from_fn(|i| self[(i, col_index)]))
+    }
+
+    /// Get the determinant of the matrix.
+    pub fn determinant(&self) -> f32 {
+        let mut m = *self;
+        let mut result = 1.0;
+        for col in 0..N {
+            let pivot = m.pivot_row(col);
+            if m[(pivot, col)] == 0.0 {
+                return 0.0;
+            }
+            if pivot != col {
+                m.0.swap(pivot, col);
+                result = -result;
+            }
+            result *= m[(col, col)];
+            for row in col + 1..N {
+                let factor = m[(row, col)] / m[(col, col)];
+                for j in col..N {
+                    let value = m[(col, j)];
+                    m[(row, j)] -= factor * value;
+                }
+            }
+        }
+        result
+    }
+
+    /// Partial pivoting: the row at or below `col` with the largest absolute value in the column.
+    fn pivot_row(&self, col: usize) -> usize {
+        let mut pivot = col;
+        for row in col + 1..N {
+            if self[(row, col)].abs() > self[(pivot, col)].abs() {
+                pivot = row;
+            }
+        }
+        pivot
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for MatN<N> {
+    type Output = f32;
+    fn index(&self, (row, col): (usize, usize)) -> &f32 {
+        &self.0[row][col]
+    }
+}
+
+impl<const N: usize> IndexMut<(usize, usize)> for MatN<N> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f32 {
+        &mut self.0[row][col]
+    }
+}
+
+impl<const N: usize> Mul for MatN<N> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| {
+            std::array::from_fn(|j| VecN::dot(self.row(i), rhs.col(j)))
+        }))
+    }
+}
+
+impl<const N: usize> Mul<VecN<N>> for MatN<N> {
+    type Output = VecN<N>;
+    fn mul(self, rhs: VecN<N>) -> VecN<N> {
+        VecN(std::array::from_fn(|i| VecN::dot(self.row(i), rhs)))
+    }
+}
@@ -1,11 +1,22 @@
+pub mod hyperplane;
 mod mat_5;
+mod mat_n;
 pub mod plane;
+mod polygon;
+mod quaternion;
+mod rotor;
+pub mod sdf;
 pub mod shape;
 pub mod space;
 mod vec_5;
+mod vec_n;
 
 pub use self::mat_5::mat5;
+pub use self::mat_n::MatN;
+pub use self::quaternion::Quaternion;
+pub use self::rotor::{Bivector4, Rotor4};
 pub use self::vec_5::vec5;
+pub use self::vec_n::VecN;
 
 use geng::prelude::*;
 
@@ -21,6 +32,22 @@ pub fn vec4_norm<T: Float>(v: vec4<T>) -> vec4<T> {
     v / len
 }
 
+/// Generalized cross product: a vector orthogonal to all three arguments,
+/// with the length equal to the volume of the parallelepiped spanned by them.
+/// Oriented so that `cross(x, y, z) = w`.
+pub fn vec4_cross<T: Float>(a: vec4<T>, b: vec4<T>, c: vec4<T>) -> vec4<T> {
+    let det3 = |a: [T; 3], b: [T; 3], c: [T; 3]| {
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    };
+    vec4(
+        -det3([a.y, a.z, a.w], [b.y, b.z, b.w], [c.y, c.z, c.w]),
+        det3([a.x, a.z, a.w], [b.x, b.z, b.w], [c.x, c.z, c.w]),
+        -det3([a.x, a.y, a.w], [b.x, b.y, b.w], [c.x, c.y, c.w]),
+        det3([a.x, a.y, a.z], [b.x, b.y, b.z], [c.x, c.y, c.z]),
+    )
+}
+
 #[derive(ugli::Vertex, Debug, Clone, Copy)]
 pub struct Vertex {
     pub a_pos: vec3<f32>,
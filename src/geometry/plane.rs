@@ -1,8 +1,8 @@
-use super::shape::Triangle;
+use super::{hyperplane::Hyperplane, polygon, sdf::Sdf3d, shape::Mesh3d, vec_n::VecN};
 
 use geng::prelude::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Plane {
     pub normal: vec3<f32>,
     pub offset: f32,
@@ -14,7 +14,133 @@ pub struct PlaneSectionVertex {
     pub projected: vec2<f32>,
 }
 
+/// Cross section of a mesh with a plane, consisting of closed contours.
+#[derive(Debug, Clone, Default)]
+pub struct PlaneSection {
+    pub contours: Vec<SectionContour>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContourKind {
+    /// Outer boundary of a filled region.
+    Outer,
+    /// Boundary of a hole in the parent contour.
+    Hole,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionContour {
+    /// Outer contours go counter-clockwise and holes go clockwise
+    /// when looking at the plane against its normal.
+    pub vertices: Vec<PlaneSectionVertex>,
+    pub kind: ContourKind,
+    /// Index of the smallest contour containing this one.
+    pub parent: Option<usize>,
+    /// Indices of the contours whose parent is this one.
+    pub children: Vec<usize>,
+}
+
+/// Measurements of a [PlaneSection] in the coordinates of [Plane::project2d].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AreaMeasurements {
+    /// Area of the filled regions, without the holes.
+    pub area: f32,
+    /// Total length of the contours, including the holes.
+    pub perimeter: f32,
+    /// Center of mass of the filled regions.
+    pub centroid: vec2<f32>,
+    /// Second moment of area `∫x² dA` about the centroid.
+    pub moment_xx: f32,
+    /// Second moment of area `∫y² dA` about the centroid.
+    pub moment_yy: f32,
+    /// Product moment of area `∫xy dA` about the centroid.
+    pub moment_xy: f32,
+}
+
+impl PlaneSection {
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// Iterate over the vertices of all contours.
+    pub fn vertices(&self) -> impl Iterator<Item = &PlaneSectionVertex> {
+        self.contours.iter().flat_map(|contour| &contour.vertices)
+    }
+
+    /// Triangulates the filled regions of the section, leaving the holes empty.
+    pub fn triangulate(&self) -> Vec<[PlaneSectionVertex; 3]> {
+        let projected =
+            |contour: &SectionContour| contour.vertices.iter().map(|v| v.projected).collect();
+        self.contours
+            .iter()
+            .filter(|contour| contour.kind == ContourKind::Outer)
+            .flat_map(|contour| {
+                let holes: Vec<&SectionContour> = contour
+                    .children
+                    .iter()
+                    .map(|&i| &self.contours[i])
+                    .collect();
+                let vertices: Vec<PlaneSectionVertex> = std::iter::once(contour)
+                    .chain(holes.iter().copied())
+                    .flat_map(|contour| contour.vertices.iter().copied())
+                    .collect();
+
+                let outer: Vec<vec2<f32>> = projected(contour);
+                let holes: Vec<Vec<vec2<f32>>> = holes.into_iter().map(projected).collect();
+                let holes: Vec<&[vec2<f32>]> = holes.iter().map(|hole| hole.as_slice()).collect();
+                polygon::triangulate(&outer, &holes)
+                    .into_iter()
+                    .map(move |triangle| triangle.map(|i| vertices[i]))
+            })
+            .collect()
+    }
+
+    /// Calculates the area, perimeter, centroid and second moments of the filled regions.
+    pub fn measure(&self) -> AreaMeasurements {
+        // Integrate over the triangles between the origin and every edge.
+        // Holes go around the other way, so they are subtracted.
+        let mut area = 0.0;
+        let mut perimeter = 0.0;
+        let mut first = vec2::ZERO;
+        let [mut xx, mut yy, mut xy] = [0.0; 3];
+        for contour in &self.contours {
+            let points: Vec<vec2<f32>> = contour.vertices.iter().map(|v| v.projected).collect();
+            for (&a, &b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                let cross = vec2::skew(a, b);
+                area += cross / 2.0;
+                perimeter += (b - a).len();
+                first += (a + b) * cross / 6.0;
+                xx += (a.x * a.x + a.x * b.x + b.x * b.x) * cross / 12.0;
+                yy += (a.y * a.y + a.y * b.y + b.y * b.y) * cross / 12.0;
+                xy += (a.x * b.y + 2.0 * a.x * a.y + 2.0 * b.x * b.y + b.x * a.y) * cross / 24.0;
+            }
+        }
+        if area.abs() < 1e-6 {
+            return AreaMeasurements {
+                perimeter,
+                ..default()
+            };
+        }
+
+        // The projection may flip the contours, the integrals are all flipped together
+        let centroid = first / area;
+        let sign = area.signum();
+        AreaMeasurements {
+            area: area.abs(),
+            perimeter,
+            centroid,
+            moment_xx: sign * xx - area.abs() * centroid.x * centroid.x,
+            moment_yy: sign * yy - area.abs() * centroid.y * centroid.y,
+            moment_xy: sign * xy - area.abs() * centroid.x * centroid.y,
+        }
+    }
+}
+
 impl Plane {
+    pub fn hyperplane(&self) -> Hyperplane<3> {
+        Hyperplane::new(self.normal.into(), self.offset)
+    }
+
     pub fn matrix(&self) -> mat4<f32> {
         let flat = vec2(self.normal.x, self.normal.z);
         let rot_h = flat.arg();
@@ -25,7 +151,7 @@ impl Plane {
     }
 
     pub fn project(&self, point: vec3<f32>) -> vec3<f32> {
-        point - self.normal.normalize_or_zero() * self.distance(point)
+        self.hyperplane().project(point.into()).into()
     }
 
     pub fn project2d(&self, point: vec3<f32>) -> vec2<f32> {
@@ -34,73 +160,281 @@ impl Plane {
         vec2(point.z, point.y)
     }
 
+    /// Inverse of [Self::project2d]: returns the point on the plane with the given coordinates.
+    pub fn unproject(&self, point: vec2<f32>) -> vec3<f32> {
+        (self.matrix().inverse() * vec4(0.0, point.y, point.x, 1.0)).into_3d()
+    }
+
     pub fn distance(&self, point: vec3<f32>) -> f32 {
-        vec3::dot(self.normal.normalize_or_zero(), point) - self.offset
+        self.hyperplane().distance(point.into())
     }
 
-    pub fn intersect_segment(&self, p1: vec3<f32>, p2: vec3<f32>) -> Option<vec3<f32>> {
-        let d1 = self.distance(p1);
-        let d2 = self.distance(p2);
+    /// Whether the sphere transformed by the `matrix` reaches the plane.
+    /// The `matrix` is expected to only rotate, scale and translate,
+    /// the radius is scaled by the largest of the scales.
+    pub fn intersects_sphere(&self, center: vec3<f32>, radius: f32, matrix: mat4<f32>) -> bool {
+        let origin = (matrix * center.extend(1.0)).into_3d();
+        let scale = [vec3::UNIT_X, vec3::UNIT_Y, vec3::UNIT_Z]
+            .into_iter()
+            .map(|axis| ((matrix * (center + axis).extend(1.0)).into_3d() - origin).len())
+            .fold(0.0, f32::max);
+        self.distance(origin).abs() <= radius * scale
+    }
 
-        if (d1 - d2).abs() < 1e-5 {
-            // Parallel
-            return None;
+    /// Calculate a cross section of the `mesh` transformed by the `matrix` with the plane.
+    ///
+    /// The mesh is sliced by [Hyperplane::slice_simplices], and the resulting segments are chained
+    /// together through the points they share, so the section of a closed mesh comes out as a set
+    /// of proper (possibly concave) contours.
+    /// Contours nested inside each other alternate between outer boundaries and holes.
+    ///
+    /// Vertices lying on the plane are classified by [is_below](super::hyperplane::is_below),
+    /// so the plane touching a vertex or containing an edge or a face gives a consistent section.
+    pub fn cross_sect(&self, mesh: &Mesh3d, matrix: mat4<f32>) -> PlaneSection {
+        // Skip the whole mesh if its bounding sphere does not reach the plane
+        let (center, radius) = mesh.bounding_sphere();
+        if !self.intersects_sphere(center, radius, matrix) {
+            return PlaneSection::default();
         }
 
-        let t = d1 / (d1 - d2);
-        (0.0..=1.0).contains(&t).then_some(p1 + t * (p2 - p1))
+        let vertices: Vec<VecN<3>> = mesh
+            .vertices()
+            .iter()
+            .map(|&v| (matrix * v.extend(1.0)).into_3d().into())
+            .collect();
+        let triangles = mesh.triangles().iter().map(|triangle| triangle.as_slice());
+        let (section, _) = self.hyperplane().slice_simplices(&vertices, triangles);
+        let segments = section
+            .simplices
+            .iter()
+            .map(|segment| [segment[0], segment[1]].map(|i| (i, section.vertices[i].into())));
+        self.section_from_segments(segments)
     }
 
-    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<(vec3<f32>, vec3<f32>)> {
-        let [a, b, c] = triangle.vertices;
-        let points: Vec<vec3<f32>> = [(a, b), (a, c), (b, c)]
-            .into_iter()
-            .flat_map(|(p1, p2)| self.intersect_segment(p1, p2))
+    /// Calculate a cross section of an object given by a signed distance function
+    /// and transformed by the `matrix` with the plane, using marching squares.
+    /// The `matrix` must be a similarity transformation,
+    /// i.e. consist of a rotation, a translation and a uniform scaling.
+    ///
+    /// The grid of `resolution` cells along each axis covers the part of the plane
+    /// inside the bounding sphere of the object.
+    pub fn cross_sect_sdf(
+        &self,
+        sdf: &dyn Sdf3d,
+        matrix: mat4<f32>,
+        resolution: usize,
+    ) -> PlaneSection {
+        let center = (matrix * vec4(0.0, 0.0, 0.0, 1.0)).into_3d();
+        let scale = ((matrix * vec4(1.0, 0.0, 0.0, 1.0)).into_3d() - center).len();
+        let radius = sdf.bounding_radius() * scale;
+        let distance = self.distance(center);
+        if distance.abs() >= radius {
+            return PlaneSection::default();
+        }
+        // Radius of the circle where the plane cuts the bounding sphere,
+        // with a margin so that the surface never touches the border of the grid
+        let half_size = (radius * radius - distance * distance).sqrt() * 1.1;
+
+        let n = resolution.max(1);
+        let cell_size = half_size * 2.0 / n as f32;
+        let min = self.project2d(center) - vec2::splat(half_size);
+        let inverse = matrix.inverse();
+        let plane_inverse = self.matrix().inverse();
+        let unproject =
+            |point: vec2<f32>| (plane_inverse * vec4(0.0, point.y, point.x, 1.0)).into_3d();
+        let position = |i: usize, j: usize| min + vec2(i as f32, j as f32) * cell_size;
+        let values: Vec<Vec<f32>> = (0..=n)
+            .map(|i| {
+                (0..=n)
+                    .map(|j| {
+                        let point = unproject(position(i, j));
+                        sdf.distance((inverse * point.extend(1.0)).into_3d())
+                    })
+                    .collect()
+            })
             .collect();
-        match &points[..] {
-            [a, b] => Some((*a, *b)),
-            _ => None,
+
+        // Edges of the grid are keyed by the first corner and the axis
+        let edge_point = |[i, j, axis]: [usize; 3]| {
+            let (a, b) = if axis == 0 {
+                ((i, j), (i + 1, j))
+            } else {
+                ((i, j), (i, j + 1))
+            };
+            let (va, vb) = (values[a.0][a.1], values[b.0][b.1]);
+            let t = va / (va - vb);
+            let (pa, pb) = (position(a.0, a.1), position(b.0, b.1));
+            ([i, j, axis], unproject(pa + (pb - pa) * t))
+        };
+
+        let mut segments = Vec::new();
+        for i in 0..n {
+            for j in 0..n {
+                // Corners and edges in counter-clockwise order
+                let corners = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)]
+                    .map(|(i, j)| values[i][j] < 0.0);
+                let edges = [[i, j, 0], [i + 1, j, 1], [i, j + 1, 0], [i, j, 1]];
+                let crossed: Vec<usize> = (0..4)
+                    .filter(|&e| corners[e] != corners[(e + 1) % 4])
+                    .collect();
+                let pairs = match crossed[..] {
+                    [a, b] => vec![[a, b]],
+                    [_, _, _, _] => {
+                        // Saddle, cut off the corners that differ from the center
+                        let center = (values[i][j]
+                            + values[i + 1][j]
+                            + values[i + 1][j + 1]
+                            + values[i][j + 1])
+                            / 4.0;
+                        if corners[0] == (center < 0.0) {
+                            vec![[0, 1], [2, 3]]
+                        } else {
+                            vec![[3, 0], [1, 2]]
+                        }
+                    }
+                    _ => continue,
+                };
+                segments.extend(
+                    pairs
+                        .into_iter()
+                        .map(|pair| pair.map(|e| edge_point(edges[e]))),
+                );
+            }
         }
+
+        self.section_from_segments(segments)
     }
 
-    /// Calculate a cross section of `geometry` with the plane.
-    pub fn cross_sect(
+    /// Chains the segments of a section into contours and builds their hierarchy.
+    fn section_from_segments<K: std::hash::Hash + Eq>(
         &self,
-        geometry: impl IntoIterator<Item = impl std::borrow::Borrow<Triangle>>,
-    ) -> Vec<PlaneSectionVertex> {
-        let mut points: Vec<PlaneSectionVertex> = Vec::new();
-        for triangle in geometry {
-            if let Some((a, b)) = self.intersect_triangle(triangle.borrow()) {
-                for p in [a, b] {
-                    let mut found = false;
-                    for q in &points {
-                        if (q.world_pos - p).len_sqr() < 1e-5 {
-                            found = true;
-                            break;
-                        }
-                    }
-                    if !found {
-                        points.push(PlaneSectionVertex {
-                            world_pos: p,
-                            projected: self.project2d(p),
-                        });
-                    }
+        segments: impl IntoIterator<Item = [(K, vec3<f32>); 2]>,
+    ) -> PlaneSection {
+        let normal = self.normal.normalize_or_zero();
+        // The points of the section already lie on the plane
+        let matrix = self.matrix();
+        let project2d = |point: vec3<f32>| {
+            let point = (matrix * point.extend(1.0)).into_3d();
+            vec2(point.z, point.y)
+        };
+
+        let mut contours: Vec<(f32, Vec<PlaneSectionVertex>)> = chain_segments(segments)
+            .into_iter()
+            .filter(|chain| chain.len() >= 3)
+            .map(|mut chain| {
+                let area = vec3::dot(polygon_normal(&chain), normal) / 2.0;
+                if area < 0.0 {
+                    chain.reverse();
+                }
+                let vertices = chain
+                    .into_iter()
+                    .map(|p| PlaneSectionVertex {
+                        world_pos: p,
+                        projected: project2d(p),
+                    })
+                    .collect();
+                (area.abs(), vertices)
+            })
+            .collect();
+
+        // Sort by area, so that the parents come before the children
+        contours.sort_by_key(|(area, _)| -r32(*area));
+
+        let projected: Vec<Vec<vec2<f32>>> = contours
+            .iter()
+            .map(|(_, vertices)| vertices.iter().map(|v| v.projected).collect())
+            .collect();
+        let mut section = PlaneSection::default();
+        for (i, (_, mut vertices)) in contours.into_iter().enumerate() {
+            // The smallest contour containing this one.
+            // Contours do not cross, but they may touch at a vertex,
+            // so test a vertex that does not lie on the other contour.
+            let parent = (0..i).rev().find(|&j| {
+                projected[i]
+                    .iter()
+                    .find(|p| !projected[j].contains(p))
+                    .is_some_and(|&p| polygon::contains_point(&projected[j], p))
+            });
+            let kind = match parent.map(|j| section.contours[j].kind) {
+                Some(ContourKind::Outer) => {
+                    vertices.reverse();
+                    ContourKind::Hole
                 }
+                _ => ContourKind::Outer,
+            };
+            if let Some(j) = parent {
+                section.contours[j].children.push(i);
             }
+            section.contours.push(SectionContour {
+                vertices,
+                kind,
+                parent,
+                children: Vec::new(),
+            });
         }
+        section
+    }
+}
 
-        if !points.is_empty() {
-            // Sort counter clockwise
-            let com = points
-                .iter()
-                .map(|p| p.projected)
-                .fold(vec2::ZERO, vec2::add)
-                / points.len() as f32;
-            points.sort_by_key(|p| -r32((p.projected - com).arg().as_radians()));
-        }
+/// Chains segments into polylines by connecting the ends that have the same key.
+/// Closed loops are returned without repeating the first point at the end.
+fn chain_segments<K: std::hash::Hash + Eq>(
+    segments: impl IntoIterator<Item = [(K, vec3<f32>); 2]>,
+) -> Vec<Vec<vec3<f32>>> {
+    let mut node_ids: HashMap<K, usize> = HashMap::new();
+    let mut positions: Vec<vec3<f32>> = Vec::new();
+    let mut node = |(key, pos): (K, vec3<f32>)| {
+        *node_ids.entry(key).or_insert_with(|| {
+            positions.push(pos);
+            positions.len() - 1
+        })
+    };
+    let segments: Vec<[usize; 2]> = segments
+        .into_iter()
+        .map(|[a, b]| [node(a), node(b)])
+        .filter(|[a, b]| a != b)
+        .collect();
 
-        points
+    let mut incident: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+    for (i, &[a, b]) in segments.iter().enumerate() {
+        incident[a].push(i);
+        incident[b].push(i);
     }
+
+    // Start from the open ends first, so that open chains are not split in the middle
+    let starts = (0..positions.len())
+        .filter(|&node| incident[node].len() % 2 == 1)
+        .chain(0..positions.len());
+
+    let mut used = vec![false; segments.len()];
+    let mut chains = Vec::new();
+    for start in starts {
+        let mut chain = vec![start];
+        let mut current = start;
+        while let Some(&segment) = incident[current].iter().find(|&&i| !used[i]) {
+            used[segment] = true;
+            let [a, b] = segments[segment];
+            current = if a == current { b } else { a };
+            chain.push(current);
+        }
+        if chain.len() < 2 {
+            continue;
+        }
+        if chain.len() > 2 && chain.first() == chain.last() {
+            chain.pop();
+        }
+        chains.push(chain.into_iter().map(|i| positions[i]).collect());
+    }
+    chains
+}
+
+/// Calculates the normal of a polygon scaled by twice its area (Newell's method).
+fn polygon_normal(points: &[vec3<f32>]) -> vec3<f32> {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&a, &b)| vec3::cross(a, b))
+        .fold(vec3::ZERO, vec3::add)
 }
 
 #[test]
@@ -151,3 +485,307 @@ fn test_plane_project() {
         check!(plane.project2d(vec3(1.0, 1.0, 2.0)), vec2(2.0, 0.0));
     }
 }
+
+#[test]
+fn test_cross_sect_concave() {
+    use super::shape::Triangle;
+
+    // L-shaped prism
+    let outline = [
+        vec2(0.0, 0.0),
+        vec2(2.0, 0.0),
+        vec2(2.0, 1.0),
+        vec2(1.0, 1.0),
+        vec2(1.0, 2.0),
+        vec2(0.0, 2.0),
+    ];
+    let triangles: Vec<Triangle> = (0..outline.len())
+        .flat_map(|i| {
+            let a = outline[i];
+            let b = outline[(i + 1) % outline.len()];
+            [
+                Triangle::new([a.extend(-1.0), b.extend(-1.0), b.extend(1.0)]),
+                Triangle::new([a.extend(-1.0), b.extend(1.0), a.extend(1.0)]),
+            ]
+        })
+        .collect();
+
+    let plane = Plane {
+        normal: vec3::UNIT_Z,
+        offset: 0.0,
+    };
+    let section = plane.cross_sect(&Mesh3d::from_triangles(triangles), mat4::identity());
+    assert_eq!(section.contours.len(), 1);
+    let section = &section.contours[0].vertices;
+
+    // Diagonals of the side quads add extra points in the middle of the straight sides
+    let corners: Vec<vec3<f32>> = (0..section.len())
+        .map(|i| {
+            let prev = section[(i + section.len() - 1) % section.len()].world_pos;
+            let next = section[(i + 1) % section.len()].world_pos;
+            (prev, section[i].world_pos, next)
+        })
+        .filter(|&(prev, v, next)| vec3::cross(v - prev, next - v).len() > 1e-5)
+        .map(|(_, v, _)| v)
+        .collect();
+    assert_eq!(corners.len(), outline.len());
+
+    let start = corners
+        .iter()
+        .position(|v| (v.xy() - outline[0]).len() < 1e-5)
+        .expect("missing a vertex");
+    for (i, expected) in outline.into_iter().enumerate() {
+        let v = corners[(start + i) % corners.len()];
+        assert!(
+            (v - expected.extend(0.0)).len() < 1e-5,
+            "vertex {i}: {v:?} != {expected:?}"
+        );
+    }
+}
+
+#[test]
+fn test_cross_sect_sdf_torus() {
+    let torus = super::sdf::Torus {
+        major_radius: 1.0,
+        minor_radius: 0.4,
+    };
+    let plane = Plane {
+        normal: vec3::UNIT_Y,
+        offset: 0.0,
+    };
+    let section = plane.cross_sect_sdf(&torus, mat4::identity(), 64);
+
+    // A ring: the outer circle with a hole in it
+    assert_eq!(section.contours.len(), 2);
+    let outer = section
+        .contours
+        .iter()
+        .position(|contour| contour.kind == ContourKind::Outer)
+        .expect("missing the outer contour");
+    for (i, contour) in section.contours.iter().enumerate() {
+        let expected = if i == outer { 1.4 } else { 0.6 };
+        for v in &contour.vertices {
+            let radius = v.world_pos.len();
+            assert!(
+                (radius - expected).abs() < 0.01,
+                "contour {i}: {radius} != {expected}"
+            );
+        }
+    }
+    let hole = 1 - outer;
+    assert_eq!(section.contours[hole].kind, ContourKind::Hole);
+    assert_eq!(section.contours[hole].parent, Some(outer));
+}
+
+#[test]
+fn test_cross_sect_degenerate() {
+    // The plane touches the vertices of the cube, or contains its edges or faces
+    let cube = super::shape::unit_cube_triangulated();
+    let cases = [
+        (vec3::UNIT_Z, 1.0, Some(4.0)),
+        (vec3::UNIT_Z, -1.0, None),
+        (
+            vec3(1.0, 1.0, 1.0),
+            1.0 / 3.0.sqrt(),
+            Some(2.0 * 3.0.sqrt()),
+        ),
+        (vec3(1.0, 1.0, 0.0), 0.0, Some(4.0 * 2.0.sqrt())),
+    ];
+    for (normal, offset, expected) in cases {
+        let plane = Plane { normal, offset };
+        let section = plane.cross_sect(&cube, mat4::identity());
+        let Some(expected) = expected else {
+            assert!(section.is_empty(), "{normal:?}: {section:?}");
+            continue;
+        };
+        assert_eq!(section.contours.len(), 1, "{normal:?}: {section:?}");
+        let points: Vec<vec3<f32>> = section.vertices().map(|v| v.world_pos).collect();
+        let area = vec3::dot(polygon_normal(&points), normal.normalize()) / 2.0;
+        assert!(
+            (area - expected).abs() < 1e-4,
+            "{normal:?}: area {area} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn test_measure() {
+    // A square of side 2 centered at (1, 0.5), the projection mirrors the x axis
+    let cube = super::shape::unit_cube_triangulated();
+    let plane = Plane {
+        normal: vec3::UNIT_Z,
+        offset: 0.3,
+    };
+    let section = plane.cross_sect(&cube, mat4::translate(vec3(1.0, 0.5, 0.0)));
+    let measure = section.measure();
+    let moment = 4.0 / 3.0;
+    assert!((measure.area - 4.0).abs() < 1e-4, "{measure:?}");
+    assert!((measure.perimeter - 8.0).abs() < 1e-4, "{measure:?}");
+    assert!(
+        (measure.centroid - vec2(-1.0, 0.5)).len() < 1e-4,
+        "{measure:?}"
+    );
+    assert!((measure.moment_xx - moment).abs() < 1e-4, "{measure:?}");
+    assert!((measure.moment_yy - moment).abs() < 1e-4, "{measure:?}");
+    assert!(measure.moment_xy.abs() < 1e-4, "{measure:?}");
+
+    // A ring: the hole is subtracted from the area, but adds to the perimeter
+    let torus = super::sdf::Torus {
+        major_radius: 1.0,
+        minor_radius: 0.4,
+    };
+    let plane = Plane {
+        normal: vec3::UNIT_Y,
+        offset: 0.0,
+    };
+    let measure = plane.cross_sect_sdf(&torus, mat4::identity(), 64).measure();
+    let area = f32::PI * (1.4 * 1.4 - 0.6 * 0.6);
+    let perimeter = 2.0 * f32::PI * (1.4 + 0.6);
+    assert!((measure.area - area).abs() < area * 0.01, "{measure:?}");
+    assert!(
+        (measure.perimeter - perimeter).abs() < perimeter * 0.01,
+        "{measure:?}"
+    );
+    assert!(measure.centroid.len() < 1e-3, "{measure:?}");
+}
+
+#[test]
+fn test_cross_sect_bounds() {
+    // A cube of side 1 rotated around the z axis, it reaches `sqrt(3) / 2` from its center
+    let cube = super::shape::unit_cube_triangulated();
+    let (center, radius) = cube.bounding_sphere();
+    assert!(center.len() < 1e-5 && (radius - 3.0.sqrt()).abs() < 1e-5);
+
+    let plane = Plane {
+        normal: vec3::UNIT_Z,
+        offset: 0.0,
+    };
+    let matrix = |z: f32| {
+        mat4::translate(vec3(1.0, 0.0, z))
+            * mat4::rotate_z(Angle::from_degrees(30.0))
+            * mat4::scale_uniform(0.5)
+    };
+    for z in [0.9, -0.9, 1e3] {
+        assert!(plane.cross_sect(&cube, matrix(z)).is_empty(), "z {z}");
+    }
+    for z in [0.45, -0.45, 0.0] {
+        let area = plane.cross_sect(&cube, matrix(z)).measure().area;
+        assert!((area - 1.0).abs() < 1e-4, "z {z}: area {area}");
+    }
+}
+
+#[test]
+fn test_cross_sect_nested() {
+    use super::shape::Triangle;
+
+    // Walls of a prism around the outline, facing outwards for counter-clockwise outlines
+    let prism = |outline: &[vec2<f32>]| -> Vec<Triangle> {
+        (0..outline.len())
+            .flat_map(|i| {
+                let a = outline[i];
+                let b = outline[(i + 1) % outline.len()];
+                [
+                    Triangle::new([a.extend(-1.0), b.extend(-1.0), b.extend(1.0)]),
+                    Triangle::new([a.extend(-1.0), b.extend(1.0), a.extend(1.0)]),
+                ]
+            })
+            .collect()
+    };
+    let square = |center: vec2<f32>, size: f32, ccw: bool| {
+        let mut outline: Vec<vec2<f32>> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .into_iter()
+            .map(|(x, y)| center + vec2(x, y) * size)
+            .collect();
+        if !ccw {
+            outline.reverse();
+        }
+        prism(&outline)
+    };
+    let plane = Plane {
+        normal: vec3::UNIT_Z,
+        offset: 0.0,
+    };
+    let triangulated_area = |section: &PlaneSection| {
+        section
+            .triangulate()
+            .iter()
+            .map(|[a, b, c]| vec2::skew(b.projected - a.projected, c.projected - a.projected) / 2.0)
+            .sum::<f32>()
+            .abs()
+    };
+
+    // An island inside a hole inside a square
+    let triangles = [
+        square(vec2::ZERO, 3.0, true),
+        square(vec2::ZERO, 2.0, false),
+        square(vec2::ZERO, 1.0, true),
+    ]
+    .concat();
+    let section = plane.cross_sect(&Mesh3d::from_triangles(triangles), mat4::identity());
+    let levels: Vec<(ContourKind, Option<usize>)> = section
+        .contours
+        .iter()
+        .map(|contour| (contour.kind, contour.parent))
+        .collect();
+    assert_eq!(
+        levels,
+        [
+            (ContourKind::Outer, None),
+            (ContourKind::Hole, Some(0)),
+            (ContourKind::Outer, Some(1)),
+        ]
+    );
+    assert_eq!(section.contours[0].children, [1]);
+    assert_eq!(section.contours[1].children, [2]);
+    let area = triangulated_area(&section);
+    assert!((area - 24.0).abs() < 1e-4, "nested: area {area}");
+
+    // Two separate squares
+    let triangles = [
+        square(vec2(-2.0, 0.0), 1.0, true),
+        square(vec2(2.0, 0.0), 1.0, true),
+    ]
+    .concat();
+    let section = plane.cross_sect(&Mesh3d::from_triangles(triangles), mat4::identity());
+    assert_eq!(section.contours.len(), 2);
+    for contour in &section.contours {
+        assert_eq!(contour.kind, ContourKind::Outer);
+        assert_eq!(contour.parent, None);
+    }
+    let area = triangulated_area(&section);
+    assert!((area - 8.0).abs() < 1e-4, "separate: area {area}");
+}
+
+#[test]
+fn test_intersects_sphere() {
+    let plane = Plane {
+        normal: vec3::UNIT_Z,
+        offset: 0.0,
+    };
+    // The sphere of radius 2 is moved from the x axis onto the z axis at `z - 2`
+    let matrix = |z: f32| {
+        mat4::translate(vec3(0.0, 0.0, z))
+            * mat4::rotate_y(Angle::from_degrees(90.0))
+            * mat4::scale_uniform(2.0)
+    };
+    let center = vec3(1.0, 0.0, 0.0);
+    for (z, expected) in [
+        (0.1, true),
+        (-0.1, false),
+        (3.9, true),
+        (4.1, false),
+        (1e3, false),
+    ] {
+        assert_eq!(
+            plane.intersects_sphere(center, 1.0, matrix(z)),
+            expected,
+            "z {z}"
+        );
+    }
+
+    // The largest scale is used for the radius
+    let matrix = |z: f32| mat4::translate(vec3(0.0, 0.0, z)) * mat4::scale(vec3(1.0, 0.5, 3.0));
+    assert!(plane.intersects_sphere(vec3::ZERO, 1.0, matrix(2.9)));
+    assert!(!plane.intersects_sphere(vec3::ZERO, 1.0, matrix(3.1)));
+    assert!(!plane.intersects_sphere(vec3::ZERO, 1.0, matrix(-3.1)));
+}
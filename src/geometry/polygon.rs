@@ -0,0 +1,302 @@
+use geng::prelude::*;
+
+/// Signed area of a polygon, positive for counter-clockwise ones.
+pub fn signed_area(points: &[vec2<f32>]) -> f32 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(&a, &b)| vec2::skew(a, b))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Checks whether the point lies inside the polygon (even-odd rule).
+pub fn contains_point(polygon: &[vec2<f32>], point: vec2<f32>) -> bool {
+    let mut inside = false;
+    for (&a, &b) in polygon.iter().zip(polygon.iter().cycle().skip(1)) {
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Triangulates a polygon with holes using ear clipping.
+/// The orientation of the contours does not matter.
+///
+/// Returns triangles as indices into the points of the `outer` contour
+/// followed by the points of every hole in order.
+pub fn triangulate(outer: &[vec2<f32>], holes: &[&[vec2<f32>]]) -> Vec<[usize; 3]> {
+    let mut points: Vec<vec2<f32>> = outer.to_vec();
+    let mut polygon: Vec<usize> = (0..outer.len()).collect();
+    if signed_area(outer) < 0.0 {
+        polygon.reverse();
+    }
+
+    let mut holes: Vec<Vec<usize>> = holes
+        .iter()
+        .filter(|hole| hole.len() >= 3)
+        .map(|hole| {
+            let start = points.len();
+            points.extend(hole.iter().copied());
+            let mut ids: Vec<usize> = (start..points.len()).collect();
+            if signed_area(hole) > 0.0 {
+                // Holes go clockwise
+                ids.reverse();
+            }
+            ids
+        })
+        .collect();
+
+    // Connect the holes to the outer contour starting from the rightmost ones
+    holes.sort_by_key(|hole| {
+        -hole
+            .iter()
+            .map(|&i| r32(points[i].x))
+            .max()
+            .unwrap_or(r32(0.0))
+    });
+    for i in 0..holes.len() {
+        let (hole, other_holes) = (&holes[i], &holes[i + 1..]);
+        // A degenerate hole with no visible vertex is left out
+        // rather than bridged across the polygon
+        if let Some(bridged) = bridge_hole(&points, &polygon, hole, other_holes) {
+            polygon = bridged;
+        }
+    }
+
+    clip_ears(&points, polygon)
+}
+
+/// Connects the hole to the polygon with a pair of coincident edges.
+/// Returns `None` if no vertex of the polygon can be connected without crossing an edge.
+fn bridge_hole(
+    points: &[vec2<f32>],
+    polygon: &[usize],
+    hole: &[usize],
+    other_holes: &[Vec<usize>],
+) -> Option<Vec<usize>> {
+    let (hole_pos, &hole_vertex) = hole
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, &i)| r32(points[i].x))
+        .expect("hole is empty");
+    let from = points[hole_vertex];
+
+    let edges = || {
+        std::iter::once(polygon)
+            .chain(std::iter::once(hole))
+            .chain(other_holes.iter().map(|hole| hole.as_slice()))
+            .flat_map(|contour| contour.iter().zip(contour.iter().cycle().skip(1)))
+            .map(|(&a, &b)| (points[a], points[b]))
+    };
+    let visible = |to: vec2<f32>| {
+        edges().all(|(a, b)| {
+            a == from || b == from || a == to || b == to || !segments_cross(from, to, a, b)
+        })
+    };
+
+    // Pick the closest visible vertex
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by_key(|&i| r32((points[polygon[i]] - from).len_sqr()));
+    let polygon_pos = candidates
+        .iter()
+        .copied()
+        .find(|&i| visible(points[polygon[i]]))?;
+
+    let mut result = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    result.extend_from_slice(&polygon[..=polygon_pos]);
+    result.extend_from_slice(&hole[hole_pos..]);
+    result.extend_from_slice(&hole[..=hole_pos]);
+    result.extend_from_slice(&polygon[polygon_pos..]);
+    Some(result)
+}
+
+/// Triangulates a counter-clockwise simple polygon.
+fn clip_ears(points: &[vec2<f32>], mut polygon: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::new();
+    let corner = |polygon: &[usize], i: usize| {
+        let n = polygon.len();
+        [polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]]
+    };
+    let turn = |[a, b, c]: [usize; 3]| vec2::skew(points[b] - points[a], points[c] - points[b]);
+
+    while polygon.len() > 3 {
+        let is_ear = |i: usize| {
+            let [a, b, c] = corner(&polygon, i);
+            turn([a, b, c]) > 1e-7
+                && polygon.iter().all(|&p| {
+                    points[p] == points[a]
+                        || points[p] == points[b]
+                        || points[p] == points[c]
+                        || !inside_triangle([points[a], points[b], points[c]], points[p])
+                })
+        };
+
+        // A spike turning back by 180 degrees, e.g. left by the ears clipped around
+        // the point where the contour touches itself, would let the ears next to it
+        // cover the outside, so it goes first
+        let is_spike = |i: usize| {
+            let [a, b, c] = corner(&polygon, i);
+            turn([a, b, c]).abs() < 1e-7
+                && vec2::dot(points[b] - points[a], points[c] - points[b]) <= 0.0
+        };
+
+        if let Some(i) = (0..polygon.len()).find(|&i| is_spike(i)) {
+            polygon.remove(i);
+        } else if let Some(i) = (0..polygon.len()).find(|&i| is_ear(i)) {
+            triangles.push(corner(&polygon, i));
+            polygon.remove(i);
+        } else if let Some(i) = (0..polygon.len()).find(|&i| turn(corner(&polygon, i)).abs() < 1e-7)
+        {
+            // Degenerate vertex, remove without producing a triangle
+            polygon.remove(i);
+        } else {
+            // Self-touching or numerically degenerate contour, any other triangle
+            // could cover the outside, so keep only the ones clipped so far
+            return triangles;
+        }
+    }
+    if polygon.len() == 3 && turn(corner(&polygon, 1)) > 1e-7 {
+        triangles.push([polygon[0], polygon[1], polygon[2]]);
+    }
+    triangles
+}
+
+/// Checks whether the point lies inside the counter-clockwise triangle or on its boundary.
+/// A vertex on the diagonal of an ear means the diagonal leaves the polygon.
+fn inside_triangle([a, b, c]: [vec2<f32>; 3], p: vec2<f32>) -> bool {
+    let ab = vec2::skew(b - a, p - a);
+    let bc = vec2::skew(c - b, p - b);
+    let ca = vec2::skew(a - c, p - c);
+    ab >= 0.0 && bc >= 0.0 && ca >= 0.0
+}
+
+fn segments_cross(a1: vec2<f32>, a2: vec2<f32>, b1: vec2<f32>, b2: vec2<f32>) -> bool {
+    let side = |a: vec2<f32>, b: vec2<f32>, p: vec2<f32>| vec2::skew(b - a, p - a);
+    let d1 = side(b1, b2, a1);
+    let d2 = side(b1, b2, a2);
+    let d3 = side(a1, a2, b1);
+    let d4 = side(a1, a2, b2);
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+#[cfg(test)]
+fn triangles_area(points: &[vec2<f32>], triangles: &[[usize; 3]]) -> f32 {
+    triangles
+        .iter()
+        .map(|&[a, b, c]| {
+            let area = vec2::skew(points[b] - points[a], points[c] - points[a]) / 2.0;
+            assert!(area > 0.0, "triangle {a} {b} {c} is not counter-clockwise");
+            area
+        })
+        .sum()
+}
+
+#[test]
+fn test_triangulate_hole() {
+    // Square ring, the hole goes the same way as the outer contour
+    let outer = [
+        vec2(-2.0, -2.0),
+        vec2(2.0, -2.0),
+        vec2(2.0, 2.0),
+        vec2(-2.0, 2.0),
+    ];
+    let hole = outer.map(|v| v / 2.0);
+    let triangles = triangulate(&outer, &[&hole]);
+
+    let points = [outer, hole].concat();
+    let area = triangles_area(&points, &triangles);
+    assert!((area - 12.0).abs() < 1e-5, "area {area}");
+    for &[a, b, c] in &triangles {
+        let center = (points[a] + points[b] + points[c]) / 3.0;
+        assert!(
+            !contains_point(&hole, center),
+            "triangle {a} {b} {c} is in the hole"
+        );
+    }
+}
+
+#[test]
+fn test_triangulate_lobes() {
+    // Two squares joined by a narrow neck, given clockwise
+    let mut outer = vec![
+        vec2(0.0, 0.0),
+        vec2(2.0, 0.0),
+        vec2(2.0, 0.9),
+        vec2(3.0, 0.9),
+        vec2(3.0, 0.0),
+        vec2(5.0, 0.0),
+        vec2(5.0, 2.0),
+        vec2(3.0, 2.0),
+        vec2(3.0, 1.1),
+        vec2(2.0, 1.1),
+        vec2(2.0, 2.0),
+        vec2(0.0, 2.0),
+    ];
+    outer.reverse();
+    let triangles = triangulate(&outer, &[]);
+    assert_eq!(triangles.len(), outer.len() - 2);
+
+    let area = triangles_area(&outer, &triangles);
+    assert!((area - 8.2).abs() < 1e-5, "area {area}");
+    for &[a, b, c] in &triangles {
+        let center = (outer[a] + outer[b] + outer[c]) / 3.0;
+        assert!(
+            contains_point(&outer, center),
+            "triangle {a} {b} {c} is outside"
+        );
+    }
+}
+
+#[test]
+fn test_triangulate_holes() {
+    // Two holes side by side, bridged to the outer contour one after another
+    let outer = [
+        vec2(0.0, 0.0),
+        vec2(6.0, 0.0),
+        vec2(6.0, 3.0),
+        vec2(0.0, 3.0),
+    ];
+    let left = [
+        vec2(1.0, 1.0),
+        vec2(2.0, 1.0),
+        vec2(2.0, 2.0),
+        vec2(1.0, 2.0),
+    ];
+    let right = left.map(|v| v + vec2(3.0, 0.0));
+    let triangles = triangulate(&outer, &[&left, &right]);
+
+    let points = [outer, left, right].concat();
+    let area = triangles_area(&points, &triangles);
+    assert!((area - 16.0).abs() < 1e-5, "area {area}");
+}
+
+#[test]
+fn test_triangulate_self_touching() {
+    // Two squares touching at a corner, as from a slice through the common edge of two cubes
+    let outer = [
+        vec2(0.0, 0.0),
+        vec2(1.0, 0.0),
+        vec2(1.0, 1.0),
+        vec2(2.0, 1.0),
+        vec2(2.0, 2.0),
+        vec2(1.0, 2.0),
+        vec2(1.0, 1.0),
+        vec2(0.0, 1.0),
+    ];
+    let triangles = triangulate(&outer, &[]);
+
+    let area = triangles_area(&outer, &triangles);
+    assert!((area - 2.0).abs() < 1e-5, "area {area}");
+    for &[a, b, c] in &triangles {
+        let center = (outer[a] + outer[b] + outer[c]) / 3.0;
+        assert!(
+            contains_point(&outer, center),
+            "triangle {a} {b} {c} is outside"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use geng::prelude::*;
+
+/// Quaternion `w + xi + yj + zk`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Self = Self {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+
+    pub fn new(w: f32, vector: vec3<f32>) -> Self {
+        Self {
+            x: vector.x,
+            y: vector.y,
+            z: vector.z,
+            w,
+        }
+    }
+
+    /// Vector (imaginary) part of the quaternion.
+    pub fn vector(&self) -> vec3<f32> {
+        vec3(self.x, self.y, self.z)
+    }
+
+    /// Exponent of the pure quaternion `xi + yj + zk`,
+    /// a unit quaternion rotating by twice the length of `v` around it.
+    pub fn exp(v: vec3<f32>) -> Self {
+        let angle = v.len();
+        if angle < 1e-6 {
+            return Self::new(1.0, v).normalize();
+        }
+        let (sin, cos) = Angle::from_radians(angle).sin_cos();
+        Self::new(cos, v / angle * sin)
+    }
+
+    /// Rotation in 3d around the axis of `v` by the angle equal to its length.
+    pub fn from_rotation_vector(v: vec3<f32>) -> Self {
+        Self::exp(v / 2.0)
+    }
+
+    /// Rotates a 3d vector by a unit quaternion.
+    pub fn rotate(self, v: vec3<f32>) -> vec3<f32> {
+        (self * Self::new(0.0, v) * self.conjugate()).vector()
+    }
+
+    /// Matrix of the 3d rotation given by a unit quaternion.
+    pub fn to_mat4(self) -> mat4<f32> {
+        let [x, y, z] = [vec3::UNIT_X, vec3::UNIT_Y, vec3::UNIT_Z].map(|axis| self.rotate(axis));
+        mat4::new([
+            [x.x, y.x, z.x, 0.0],
+            [x.y, y.y, z.y, 0.0],
+            [x.z, y.z, z.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn conjugate(self) -> Self {
+        Self::new(self.w, -self.vector())
+    }
+
+    pub fn dot(a: Self, b: Self) -> f32 {
+        a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w
+    }
+
+    pub fn len(self) -> f32 {
+        Self::dot(self, self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        let len = self.len();
+        if len < 1e-6 {
+            return Self::IDENTITY;
+        }
+        self * (1.0 / len)
+    }
+
+    /// Interpolates between two unit quaternions along the great arc connecting them,
+    /// without taking the shortest path.
+    pub(super) fn arc(self, other: Self, t: f32) -> Self {
+        let cos = Self::dot(self, other).clamp(-1.0, 1.0);
+        let angle = cos.acos();
+        if angle.sin() < 1e-5 {
+            // Too close for the division, linear interpolation is precise enough
+            return (self * (1.0 - t) + other * t).normalize();
+        }
+        let sin = angle.sin();
+        self * (((1.0 - t) * angle).sin() / sin) + other * ((t * angle).sin() / sin)
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.w + rhs.w, self.vector() + rhs.vector())
+    }
+}
+
+impl Neg for Quaternion {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.w, -self.vector())
+    }
+}
+
+impl Mul<f32> for Quaternion {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.w * rhs, self.vector() * rhs)
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+    /// Hamilton product.
+    fn mul(self, rhs: Self) -> Self {
+        let (a, b) = (self.vector(), rhs.vector());
+        Self::new(
+            self.w * rhs.w - vec3::dot(a, b),
+            b * self.w + a * rhs.w + vec3::cross(a, b),
+        )
+    }
+}
@@ -0,0 +1,257 @@
+use geng::prelude::*;
+
+use super::{mat5, quaternion::Quaternion, vec4_len, vec4_norm};
+
+/// Bivector in 4d: a combination of rotations in the six coordinate planes.
+/// Each component is the angle (or the angular velocity) in its plane,
+/// positive values rotating the first axis towards the second one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Bivector4 {
+    pub xy: f32,
+    pub xz: f32,
+    pub xw: f32,
+    pub yz: f32,
+    pub yw: f32,
+    pub zw: f32,
+}
+
+impl Bivector4 {
+    pub const ZERO: Self = Self {
+        xy: 0.0,
+        xz: 0.0,
+        xw: 0.0,
+        yz: 0.0,
+        yw: 0.0,
+        zw: 0.0,
+    };
+
+    /// Bivector of the plane spanned by `a` and `b`, rotating `a` towards `b`.
+    /// Its length is the area of the parallelogram, so orthonormal vectors give a unit rotation.
+    pub fn wedge(a: vec4<f32>, b: vec4<f32>) -> Self {
+        Self {
+            xy: a.x * b.y - a.y * b.x,
+            xz: a.x * b.z - a.z * b.x,
+            xw: a.x * b.w - a.w * b.x,
+            yz: a.y * b.z - a.z * b.y,
+            yw: a.y * b.w - a.w * b.y,
+            zw: a.z * b.w - a.w * b.z,
+        }
+    }
+
+    pub fn len(&self) -> f32 {
+        (self.xy * self.xy
+            + self.xz * self.xz
+            + self.xw * self.xw
+            + self.yz * self.yz
+            + self.yw * self.yw
+            + self.zw * self.zw)
+            .sqrt()
+    }
+}
+
+impl Add for Bivector4 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            xy: self.xy + rhs.xy,
+            xz: self.xz + rhs.xz,
+            xw: self.xw + rhs.xw,
+            yz: self.yz + rhs.yz,
+            yw: self.yw + rhs.yw,
+            zw: self.zw + rhs.zw,
+        }
+    }
+}
+
+impl Mul<f32> for Bivector4 {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self {
+        Self {
+            xy: self.xy * rhs,
+            xz: self.xz * rhs,
+            xw: self.xw * rhs,
+            yz: self.yz * rhs,
+            yw: self.yw * rhs,
+            zw: self.zw * rhs,
+        }
+    }
+}
+
+/// Rotation in 4d as a pair of unit quaternions.
+/// A point `(x, y, z, w)` is treated as the quaternion `w + xi + yj + zk`
+/// and is rotated into `left * p * right`.
+///
+/// Multiplying by a unit quaternion on either side is an isoclinic rotation,
+/// so any rotation, including double rotations, is a product of the two.
+/// `(left, right)` and `(-left, -right)` represent the same rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rotor4 {
+    pub left: Quaternion,
+    pub right: Quaternion,
+}
+
+impl Rotor4 {
+    pub const IDENTITY: Self = Self {
+        left: Quaternion::IDENTITY,
+        right: Quaternion::IDENTITY,
+    };
+
+    pub fn new(left: Quaternion, right: Quaternion) -> Self {
+        Self {
+            left: left.normalize(),
+            right: right.normalize(),
+        }
+    }
+
+    /// Rotation by the angles given by the bivector, all happening simultaneously.
+    pub fn from_bivector(b: Bivector4) -> Self {
+        // The bivector splits into the left and the right isoclinic parts, which commute
+        let left = vec3(b.yz - b.xw, -b.xz - b.yw, b.xy - b.zw) / 2.0;
+        let right = vec3(-b.yz - b.xw, b.xz - b.yw, -b.xy - b.zw) / 2.0;
+        Self {
+            left: Quaternion::exp(left),
+            right: Quaternion::exp(right),
+        }
+    }
+
+    /// Simple rotation turning the unit vector `from` onto the unit vector `to` in their plane.
+    /// Opposite vectors have no common plane, so they turn towards the `fallback`,
+    /// which must be a unit vector orthogonal to `from`.
+    pub fn between(from: vec4<f32>, to: vec4<f32>, fallback: vec4<f32>) -> Self {
+        let cos = vec4::dot(from, to).clamp(-1.0, 1.0);
+        let towards = to - from * cos;
+        let towards = if vec4_len(towards) < 1e-4 {
+            fallback
+        } else {
+            vec4_norm(towards)
+        };
+        Self::from_bivector(Bivector4::wedge(from, towards) * cos.acos())
+    }
+
+    pub fn rotate(&self, v: vec4<f32>) -> vec4<f32> {
+        let p = self.left * Quaternion::new(v.w, v.xyz()) * self.right;
+        vec4(p.x, p.y, p.z, p.w)
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self {
+            left: self.left.conjugate(),
+            right: self.right.conjugate(),
+        }
+    }
+
+    /// Renormalizes the quaternions to fix the accumulated precision errors.
+    pub fn normalize(&self) -> Self {
+        Self::new(self.left, self.right)
+    }
+
+    /// Interpolates between two rotations along the shortest path.
+    pub fn slerp(&self, other: &Self, t: f32) -> Self {
+        // Choose the sign of the other pair closest to this one
+        let dot = Quaternion::dot(self.left, other.left) + Quaternion::dot(self.right, other.right);
+        let (left, right) = if dot < 0.0 {
+            (-other.left, -other.right)
+        } else {
+            (other.left, other.right)
+        };
+        Self::new(self.left.arc(left, t), self.right.arc(right, t))
+    }
+
+    pub fn to_mat5(&self) -> mat5<f32> {
+        let [x, y, z, w] =
+            [vec4::UNIT_X, vec4::UNIT_Y, vec4::UNIT_Z, vec4::UNIT_W].map(|axis| self.rotate(axis));
+        mat5::new([
+            [x.x, y.x, z.x, w.x, 0.0],
+            [x.y, y.y, z.y, w.y, 0.0],
+            [x.z, y.z, z.z, w.z, 0.0],
+            [x.w, y.w, z.w, w.w, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Mul for Rotor4 {
+    type Output = Self;
+    /// Composition of the rotations: `rhs` is applied first.
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            left: self.left * rhs.left,
+            right: rhs.right * self.right,
+        }
+    }
+}
+
+#[test]
+fn test_rotor() {
+    let check = |a: vec4<f32>, b: vec4<f32>| {
+        let delta = vec4::dot(a - b, a - b).sqrt();
+        assert!(delta < 1e-5, "{a:?} != {b:?}");
+    };
+    let quarter = f32::PI / 2.0;
+
+    // Simple rotations
+    let rotor = Rotor4::from_bivector(Bivector4 {
+        xy: quarter,
+        ..Bivector4::ZERO
+    });
+    check(rotor.rotate(vec4::UNIT_X), vec4::UNIT_Y);
+    check(rotor.rotate(vec4::UNIT_Z), vec4::UNIT_Z);
+    check(rotor.rotate(vec4::UNIT_W), vec4::UNIT_W);
+    let rotor = Rotor4::from_bivector(Bivector4 {
+        yw: quarter,
+        ..Bivector4::ZERO
+    });
+    check(rotor.rotate(vec4::UNIT_Y), vec4::UNIT_W);
+    check(rotor.rotate(vec4::UNIT_X), vec4::UNIT_X);
+    check(rotor.rotate(vec4::UNIT_Z), vec4::UNIT_Z);
+
+    // Double rotation
+    let double = Rotor4::from_bivector(Bivector4 {
+        xz: quarter,
+        yw: quarter / 2.0,
+        ..Bivector4::ZERO
+    });
+    check(double.rotate(vec4::UNIT_X), vec4::UNIT_Z);
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    check(double.rotate(vec4::UNIT_Y), vec4(0.0, half, 0.0, half));
+
+    // Composition and inverse
+    let point = vec4(1.0, -2.0, 0.5, 3.0);
+    check(
+        (double * rotor).rotate(point),
+        double.rotate(rotor.rotate(point)),
+    );
+    check((double * double.inverse()).rotate(point), point);
+    let matrix = double.to_mat5() * super::vec5(point.x, point.y, point.z, point.w, 1.0);
+    check(matrix.xyzw(), double.rotate(point));
+
+    // Interpolation
+    let full = Rotor4::from_bivector(Bivector4 {
+        xw: quarter,
+        ..Bivector4::ZERO
+    });
+    let halfway = Rotor4::IDENTITY.slerp(&full, 0.5);
+    check(halfway.rotate(vec4::UNIT_X), vec4(half, 0.0, 0.0, half));
+
+    // Rotation in an arbitrary plane
+    let a = vec4(1.0, 1.0, 0.0, 0.0) * half;
+    let b = vec4(0.0, 0.0, 1.0, -1.0) * half;
+    let rotor = Rotor4::from_bivector(Bivector4::wedge(a, b) * quarter);
+    check(rotor.rotate(a), b);
+    check(rotor.rotate(b), -a);
+    check(
+        rotor.rotate(vec4(1.0, -1.0, 1.0, 1.0)),
+        vec4(1.0, -1.0, 1.0, 1.0),
+    );
+
+    // Turning between two vectors, also opposite ones
+    let rotor = Rotor4::between(vec4::UNIT_W, b, vec4::UNIT_X);
+    check(rotor.rotate(vec4::UNIT_W), b);
+    check(rotor.rotate(vec4::UNIT_X), vec4::UNIT_X);
+    let rotor = Rotor4::between(vec4::UNIT_W, -vec4::UNIT_W, vec4::UNIT_X);
+    check(rotor.rotate(vec4::UNIT_W), -vec4::UNIT_W);
+    check(rotor.rotate(vec4::UNIT_Y), vec4::UNIT_Y);
+    // Halfway through the opposite turn, both directions in the plane are the shortest
+    let halfway = Rotor4::IDENTITY.slerp(&rotor, 0.5).rotate(vec4::UNIT_W);
+    check(halfway * halfway.x.signum(), vec4::UNIT_X);
+}
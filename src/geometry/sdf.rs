@@ -0,0 +1,289 @@
+//! Objects defined implicitly by signed distance functions:
+//! negative inside the object, positive outside, zero on the surface.
+
+use super::shape::Mesh3d;
+
+use geng::prelude::*;
+
+pub trait Sdf3d: Send + Sync {
+    fn distance(&self, point: vec3<f32>) -> f32;
+    /// Radius of the ball around the origin containing the whole object.
+    fn bounding_radius(&self) -> f32;
+}
+
+pub trait Sdf4d: Send + Sync {
+    fn distance(&self, point: vec4<f32>) -> f32;
+    /// Radius of the ball around the origin containing the whole object.
+    fn bounding_radius(&self) -> f32;
+}
+
+#[derive(Debug, Clone)]
+pub struct Sphere {
+    pub center: vec3<f32>,
+    pub radius: f32,
+}
+
+impl Sdf3d for Sphere {
+    fn distance(&self, point: vec3<f32>) -> f32 {
+        (point - self.center).len() - self.radius
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.center.len() + self.radius
+    }
+}
+
+/// 3-sphere.
+#[derive(Debug, Clone)]
+pub struct Glome {
+    pub center: vec4<f32>,
+    pub radius: f32,
+}
+
+impl Sdf4d for Glome {
+    fn distance(&self, point: vec4<f32>) -> f32 {
+        let delta = point - self.center;
+        vec4::dot(delta, delta).sqrt() - self.radius
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        vec4::dot(self.center, self.center).sqrt() + self.radius
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cuboid {
+    pub half_size: vec3<f32>,
+}
+
+impl Sdf3d for Cuboid {
+    fn distance(&self, point: vec3<f32>) -> f32 {
+        let q = point.map(f32::abs) - self.half_size;
+        q.map(|x| x.max(0.0)).len() + q.x.max(q.y).max(q.z).min(0.0)
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.half_size.len()
+    }
+}
+
+/// Torus around the y axis.
+#[derive(Debug, Clone)]
+pub struct Torus {
+    pub major_radius: f32,
+    pub minor_radius: f32,
+}
+
+impl Sdf3d for Torus {
+    fn distance(&self, point: vec3<f32>) -> f32 {
+        let q = vec2(vec2(point.x, point.z).len() - self.major_radius, point.y);
+        q.len() - self.minor_radius
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.major_radius + self.minor_radius
+    }
+}
+
+/// Menger sponge fitting in a cube with the given half size.
+#[derive(Debug, Clone)]
+pub struct MengerSponge {
+    pub half_size: f32,
+    pub iterations: usize,
+}
+
+impl Sdf3d for MengerSponge {
+    fn distance(&self, point: vec3<f32>) -> f32 {
+        let point = point / self.half_size;
+        let mut distance = Cuboid {
+            half_size: vec3::splat(1.0),
+        }
+        .distance(point);
+
+        // Carve out the crosses at every scale
+        let mut scale = 1.0;
+        for _ in 0..self.iterations {
+            let a = (point * scale).map(|x| x.rem_euclid(2.0) - 1.0);
+            scale *= 3.0;
+            let r = a.map(|x| 1.0 - 3.0 * x.abs()).map(f32::abs);
+            let cross = r.x.max(r.y).min(r.y.max(r.z)).min(r.z.max(r.x));
+            distance = distance.max((cross - 1.0) / scale);
+        }
+        distance * self.half_size
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.half_size * 3.0.sqrt()
+    }
+}
+
+/// Smoothly blended union of several objects, `smoothness` being the size of the blending region.
+#[derive(Debug, Clone)]
+pub struct SmoothUnion<T> {
+    pub parts: Vec<T>,
+    pub smoothness: f32,
+}
+
+impl<T> SmoothUnion<T> {
+    fn blend(&self, distances: impl Iterator<Item = f32>) -> f32 {
+        let k = self.smoothness;
+        distances
+            .reduce(|a, b| {
+                if k <= 0.0 {
+                    return a.min(b);
+                }
+                let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+                b + (a - b) * h - k * h * (1.0 - h)
+            })
+            .unwrap_or(f32::INFINITY)
+    }
+
+    fn blend_radius(&self, radii: impl Iterator<Item = f32>) -> f32 {
+        // Smooth minimum is at most `k / 4` smaller than the minimum
+        radii.fold(0.0, f32::max) + self.smoothness.max(0.0) / 4.0
+    }
+}
+
+impl<T: Sdf3d> Sdf3d for SmoothUnion<T> {
+    fn distance(&self, point: vec3<f32>) -> f32 {
+        self.blend(self.parts.iter().map(|part| part.distance(point)))
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.blend_radius(self.parts.iter().map(|part| part.bounding_radius()))
+    }
+}
+
+impl<T: Sdf4d> Sdf4d for SmoothUnion<T> {
+    fn distance(&self, point: vec4<f32>) -> f32 {
+        self.blend(self.parts.iter().map(|part| part.distance(point)))
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.blend_radius(self.parts.iter().map(|part| part.bounding_radius()))
+    }
+}
+
+/// Triangulates the surface of a 3d object, used to draw it.
+pub fn polygonize(sdf: &dyn Sdf3d, resolution: usize) -> Mesh3d {
+    // Leave a margin, so that the surface never touches the border of the grid
+    let half_size = sdf.bounding_radius() * 1.1;
+    marching_tetrahedra(vec3::ZERO, half_size, resolution, |point| {
+        sdf.distance(point)
+    })
+}
+
+/// Extracts the zero level surface of the `field` inside the cube
+/// with the given center and half size, split into `resolution` cells along each axis.
+/// Every cell is split into 6 tetrahedra, so that the surface comes out watertight
+/// without the ambiguous cases of marching cubes.
+///
+/// Triangles are oriented to face the positive side of the field.
+pub fn marching_tetrahedra(
+    center: vec3<f32>,
+    half_size: f32,
+    resolution: usize,
+    field: impl Fn(vec3<f32>) -> f32,
+) -> Mesh3d {
+    let n = resolution.max(1);
+    let cell_size = half_size * 2.0 / n as f32;
+    let min = center - vec3::splat(half_size);
+    let index = |i: usize, j: usize, k: usize| (i * (n + 1) + j) * (n + 1) + k;
+    let position = |id: usize| {
+        let (i, j, k) = (
+            id / ((n + 1) * (n + 1)),
+            id / (n + 1) % (n + 1),
+            id % (n + 1),
+        );
+        min + vec3(i as f32, j as f32, k as f32) * cell_size
+    };
+    let values: Vec<f32> = (0..(n + 1) * (n + 1) * (n + 1))
+        .map(|id| field(position(id)))
+        .collect();
+
+    let edge_point = |a: usize, b: usize| {
+        let t = values[a] / (values[a] - values[b]);
+        position(a) + (position(b) - position(a)) * t
+    };
+    let mut vertices: Vec<vec3<f32>> = Vec::new();
+    let mut edge_vertices: HashMap<[usize; 2], usize> = HashMap::new();
+    let mut edge_vertex = |a: usize, b: usize| {
+        let key = [a.min(b), a.max(b)];
+        let point = edge_point(key[0], key[1]);
+        let id = *edge_vertices.entry(key).or_insert_with(|| {
+            vertices.push(point);
+            vertices.len() - 1
+        });
+        (id, point)
+    };
+
+    // Kuhn triangulation of the cube: paths from the min to the max corner along the axes
+    let paths = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    let mut triangles = Vec::new();
+    for i in 0..n {
+        for j in 0..n {
+            for k in 0..n {
+                let inside_corners = (0..8)
+                    .filter(|c| {
+                        values[index(i + (c >> 2 & 1), j + (c >> 1 & 1), k + (c & 1))] < 0.0
+                    })
+                    .count();
+                if inside_corners == 0 || inside_corners == 8 {
+                    // The surface does not pass through the cell
+                    continue;
+                }
+
+                for path in paths {
+                    let mut corner = [i, j, k];
+                    let mut tetrahedron = [index(i, j, k); 4];
+                    for (step, axis) in path.into_iter().enumerate() {
+                        corner[axis] += 1;
+                        tetrahedron[step + 1] = index(corner[0], corner[1], corner[2]);
+                    }
+
+                    let (inside, outside): (Vec<usize>, Vec<usize>) =
+                        tetrahedron.into_iter().partition(|&v| values[v] < 0.0);
+                    let points: Vec<(usize, vec3<f32>)> = match (&inside[..], &outside[..]) {
+                        (&[a], &[b, c, d]) | (&[b, c, d], &[a]) => {
+                            vec![edge_vertex(a, b), edge_vertex(a, c), edge_vertex(a, d)]
+                        }
+                        (&[a, b], &[c, d]) => vec![
+                            edge_vertex(a, c),
+                            edge_vertex(a, d),
+                            edge_vertex(b, d),
+                            edge_vertex(b, c),
+                        ],
+                        _ => continue,
+                    };
+
+                    // Face from the inside to the outside
+                    let centroid = |ids: &[usize]| {
+                        ids.iter().map(|&v| position(v)).fold(vec3::ZERO, vec3::add)
+                            / ids.len() as f32
+                    };
+                    let direction = centroid(&outside) - centroid(&inside);
+                    for corners in [[0, 1, 2], [0, 2, 3]] {
+                        if corners[2] >= points.len() {
+                            continue;
+                        }
+                        let [mut a, mut b, c] = corners.map(|i| points[i]);
+                        if vec3::dot(vec3::cross(b.1 - a.1, c.1 - a.1), direction) < 0.0 {
+                            std::mem::swap(&mut a, &mut b);
+                        }
+                        triangles.push([a.0, b.0, c.0]);
+                    }
+                }
+            }
+        }
+    }
+
+    Mesh3d::new(vertices, triangles)
+}
@@ -0,0 +1,401 @@
+use super::*;
+
+use super::{polytopes::pull_cell, products::duoprism};
+
+/// Sphere of radius 1 tessellated along meridians and parallels,
+/// with `resolution` segments around the equator.
+pub fn unit_uv_sphere(resolution: usize) -> Mesh3d {
+    let rings = (resolution / 2).max(2);
+    let profile: Vec<vec2<f32>> = (0..=rings)
+        .map(|i| {
+            let angle = Angle::from_radians(i as f32 / rings as f32 * f32::PI);
+            let (sin, cos) = angle.sin_cos();
+            vec2(sin, -cos)
+        })
+        .collect();
+    revolve(&profile, false, resolution)
+}
+
+/// Sphere of radius 1 made by subdividing the faces of the icosahedron,
+/// with about `resolution` segments around the equator.
+/// Unlike [unit_uv_sphere], the triangles are all of about the same size.
+pub fn unit_icosphere(resolution: usize) -> Mesh3d {
+    let frequency = resolution.div_ceil(5).max(1);
+    let icosahedron = unit_icosahedron_triangulized();
+    let corners = icosahedron.vertices();
+
+    // Points are given by the integer weights of the corners,
+    // so the neighbouring faces share the points on their common edge
+    let mut vertices = Vec::new();
+    let mut vertex_ids: HashMap<Vec<(usize, usize)>, usize> = HashMap::new();
+    let mut vertex = |weights: [(usize, usize); 3]| {
+        let mut key: Vec<(usize, usize)> = weights
+            .into_iter()
+            .filter(|&(_, weight)| weight > 0)
+            .collect();
+        key.sort();
+        *vertex_ids.entry(key).or_insert_with_key(|key| {
+            let point = key.iter().fold(vec3::ZERO, |acc, &(i, weight)| {
+                acc + corners[i] * weight as f32
+            });
+            vertices.push(point.normalize_or_zero());
+            vertices.len() - 1
+        })
+    };
+
+    let mut triangles = Vec::new();
+    for &[a, b, c] in icosahedron.triangles() {
+        let mut point = |i: usize, j: usize| vertex([(a, frequency - i - j), (b, i), (c, j)]);
+        for i in 0..frequency {
+            for j in 0..frequency - i {
+                triangles.push([point(i, j), point(i + 1, j), point(i, j + 1)]);
+                if i + j + 1 < frequency {
+                    triangles.push([point(i + 1, j), point(i + 1, j + 1), point(i, j + 1)]);
+                }
+            }
+        }
+    }
+
+    Mesh3d::new(vertices, triangles)
+}
+
+/// Cylinder of radius 1 along the y axis from -1 to 1,
+/// with `resolution` segments around the axis.
+pub fn unit_cylinder(resolution: usize) -> Mesh3d {
+    let profile = [
+        vec2(0.0, -1.0),
+        vec2(1.0, -1.0),
+        vec2(1.0, 1.0),
+        vec2(0.0, 1.0),
+    ];
+    revolve(&profile, false, resolution)
+}
+
+/// Cone with the base of radius 1 at y = -1 and the apex at y = 1,
+/// with `resolution` segments around the axis.
+pub fn unit_cone(resolution: usize) -> Mesh3d {
+    let profile = [vec2(0.0, -1.0), vec2(1.0, -1.0), vec2(0.0, 1.0)];
+    revolve(&profile, false, resolution)
+}
+
+/// Torus around the y axis with the major radius of 1 and the minor radius of 0.4,
+/// with `resolution` segments around the axis.
+pub fn unit_torus(resolution: usize) -> Mesh3d {
+    let tube_resolution = (resolution / 2).max(3);
+    let profile: Vec<vec2<f32>> = (0..tube_resolution)
+        .map(|i| {
+            let angle = Angle::from_radians(i as f32 / tube_resolution as f32 * 2.0 * f32::PI);
+            let (sin, cos) = angle.sin_cos();
+            vec2(1.0 + cos * 0.4, sin * 0.4)
+        })
+        .collect();
+    revolve(&profile, true, resolution)
+}
+
+/// Surface of revolution around the y axis.
+/// The `profile` is given as `(radius, height)` pairs and should go counter-clockwise,
+/// so that the normals point outwards.
+/// Points with zero radius are on the axis and become single vertices.
+/// If `closed` is true the last point of the profile is connected to the first one.
+fn revolve(profile: &[vec2<f32>], closed: bool, resolution: usize) -> Mesh3d {
+    let resolution = resolution.max(3);
+
+    let mut vertices = Vec::new();
+    let rings: Vec<Vec<usize>> = profile
+        .iter()
+        .map(|&point| {
+            if point.x.abs() < 1e-5 {
+                // Pole
+                vertices.push(vec3(0.0, point.y, 0.0));
+                vec![vertices.len() - 1; resolution]
+            } else {
+                (0..resolution)
+                    .map(|i| {
+                        let angle =
+                            Angle::from_radians(i as f32 / resolution as f32 * 2.0 * f32::PI);
+                        let (sin, cos) = angle.sin_cos();
+                        vertices.push(vec3(cos * point.x, point.y, sin * point.x));
+                        vertices.len() - 1
+                    })
+                    .collect()
+            }
+        })
+        .collect();
+
+    let segments = if closed {
+        profile.len()
+    } else {
+        profile.len().saturating_sub(1)
+    };
+    let mut triangles = Vec::new();
+    for k in 0..segments {
+        let (lower, upper) = (&rings[k], &rings[(k + 1) % rings.len()]);
+        for i in 0..resolution {
+            let j = (i + 1) % resolution;
+            for triangle in [
+                [lower[i], upper[i], upper[j]],
+                [lower[i], upper[j], lower[j]],
+            ] {
+                let [a, b, c] = triangle;
+                if a != b && b != c && c != a {
+                    triangles.push(triangle);
+                }
+            }
+        }
+    }
+
+    Mesh3d::new(vertices, triangles)
+}
+
+/// Glome (3-sphere) of radius 1, with `resolution` segments around its great circles.
+pub fn unit_glome(resolution: usize) -> Mesh4d {
+    let resolution = resolution.max(3);
+    let layers = (resolution / 4).max(2);
+    let circle = unit_polygon(resolution);
+
+    // Hopf coordinates: two circles in the xy and zw planes, scaled by cos and sin of eta
+    let mut points = Vec::new();
+    for k in 0..=layers {
+        let eta = Angle::from_radians(k as f32 / layers as f32 * f32::PI / 2.0);
+        let (sin_eta, cos_eta) = eta.sin_cos();
+        // At the ends one of the circles collapses into a point
+        let first = if k == layers {
+            &circle[..1]
+        } else {
+            &circle[..]
+        };
+        let second = if k == 0 { &circle[..1] } else { &circle[..] };
+        for &(cos1, sin1) in first {
+            for &(cos2, sin2) in second {
+                points.push(vec4(
+                    cos_eta * cos1,
+                    cos_eta * sin1,
+                    sin_eta * cos2,
+                    sin_eta * sin2,
+                ));
+            }
+        }
+    }
+
+    Mesh4d::from_tetrahedra(convex_hull_4d(&points))
+}
+
+/// Spherinder, the prism of the sphere of radius 1 along the w axis from -1 to 1.
+pub fn unit_spherinder(resolution: usize) -> Mesh4d {
+    unit_prism(&unit_uv_sphere(resolution))
+}
+
+/// Cubinder, the cartesian product of a disk of radius 1 and a square with the side of 2.
+pub fn unit_cubinder(resolution: usize) -> Mesh4d {
+    unit_prism(&unit_cylinder(resolution))
+}
+
+/// Duocylinder, the cartesian product of two disks of radius 1
+/// in the xy and zw planes.
+pub fn unit_duocylinder(resolution: usize) -> Mesh4d {
+    duoprism(resolution.max(3), resolution.max(3), 1.0)
+}
+
+/// Tiger, the set of points at the distance of 0.3 from the flat torus
+/// `sqrt(x^2 + y^2) = 0.7`, `sqrt(z^2 + w^2) = 0.7`.
+pub fn unit_tiger(resolution: usize) -> Mesh4d {
+    let (major, minor) = (0.7, 0.3);
+    let resolution = resolution.max(3);
+    let tube_resolution = (resolution / 2).max(3);
+    let circle = unit_polygon(resolution);
+    let tube = unit_polygon(tube_resolution);
+
+    // Torus of the xy circle and the tube, swept around the zw circle
+    let index = |i: usize, j: usize| (i % resolution) * tube_resolution + j % tube_resolution;
+    let triangles: Vec<[usize; 3]> = (0..resolution)
+        .flat_map(|i| {
+            (0..tube_resolution).flat_map(move |j| {
+                [
+                    [index(i, j), index(i + 1, j), index(i + 1, j + 1)],
+                    [index(i, j), index(i + 1, j + 1), index(i, j + 1)],
+                ]
+            })
+        })
+        .collect();
+    sweep_around_circle(
+        resolution * tube_resolution,
+        &triangles,
+        resolution,
+        |vertex, (cos2, sin2)| {
+            let (cos1, sin1) = circle[vertex / tube_resolution];
+            let (cos, sin) = tube[vertex % tube_resolution];
+            let (r1, r2) = (major + minor * cos, major + minor * sin);
+            vec4(r1 * cos1, r1 * sin1, r2 * cos2, r2 * sin2)
+        },
+    )
+}
+
+/// Spheritorus, the set of points satisfying
+/// `(sqrt(x^2 + y^2 + z^2) - 0.7)^2 + w^2 = 0.3^2`.
+pub fn unit_spheritorus(resolution: usize) -> Mesh4d {
+    let (major, minor) = (0.7, 0.3);
+    let sphere = unit_uv_sphere(resolution);
+    sweep_around_circle(
+        sphere.vertices().len(),
+        sphere.triangles(),
+        (resolution / 2).max(3),
+        |vertex, (cos, sin)| {
+            (sphere.vertices()[vertex] * (major + minor * cos)).extend(minor * sin)
+        },
+    )
+}
+
+/// Sweeps a closed triangulated surface around a circle with `resolution` segments.
+/// `point(vertex, (cos, sin))` places the vertex of the surface
+/// at the given point on the circle.
+/// Every triangle times every arc of the circle gives a triangular prism cell.
+fn sweep_around_circle(
+    vertex_count: usize,
+    triangles: &[[usize; 3]],
+    resolution: usize,
+    point: impl Fn(usize, (f32, f32)) -> vec4<f32>,
+) -> Mesh4d {
+    let circle = unit_polygon(resolution);
+    let vertices: Vec<vec4<f32>> = (0..vertex_count)
+        .flat_map(|vertex| circle.iter().map(move |&angle| (vertex, angle)))
+        .map(|(vertex, angle)| point(vertex, angle))
+        .collect();
+    let index = |vertex: usize, i: usize| vertex * resolution + i % resolution;
+
+    let mut cells = Vec::new();
+    for &[a, b, c] in triangles {
+        for i in 0..resolution {
+            let faces = [
+                vec![index(a, i), index(b, i), index(c, i)],
+                vec![index(a, i + 1), index(b, i + 1), index(c, i + 1)],
+                vec![index(a, i), index(b, i), index(b, i + 1), index(a, i + 1)],
+                vec![index(b, i), index(c, i), index(c, i + 1), index(b, i + 1)],
+                vec![index(c, i), index(a, i), index(a, i + 1), index(c, i + 1)],
+            ];
+            cells.extend(pull_cell(&faces));
+        }
+    }
+
+    Mesh4d::new(vertices, cells)
+}
+
+#[test]
+fn test_curved_3d() {
+    use crate::geometry::plane::Plane;
+
+    // Name, mesh, volume, area of the section by the plane y = 0.5
+    let pi = f32::PI;
+    let shapes = [
+        ("uv sphere", unit_uv_sphere(48), 4.0 / 3.0 * pi, 0.75 * pi),
+        ("icosphere", unit_icosphere(48), 4.0 / 3.0 * pi, 0.75 * pi),
+        ("cylinder", unit_cylinder(48), 2.0 * pi, pi),
+        ("cone", unit_cone(48), 2.0 / 3.0 * pi, 0.0625 * pi),
+        ("torus", unit_torus(48), 0.32 * pi * pi, 0.0),
+    ];
+    let plane = Plane {
+        normal: vec3::UNIT_Y,
+        offset: 0.5,
+    };
+    for (name, mesh, volume, area) in shapes {
+        mesh::assert_closed_3d(&mesh);
+        let measured = mesh.measure().volume;
+        assert!(
+            (measured - volume).abs() < volume * 0.02,
+            "{name}: {measured} != {volume}"
+        );
+
+        let section = plane.cross_sect(&mesh, mat4::identity());
+        let measured = section.measure().area;
+        assert!(
+            (measured - area).abs() < area * 0.02 + 1e-4,
+            "{name}: section {measured} != {area}"
+        );
+    }
+
+    // Sections of the torus close to its equator are rings
+    let section = Plane {
+        normal: vec3::UNIT_Y,
+        offset: 0.1,
+    }
+    .cross_sect(&unit_torus(48), mat4::identity());
+    assert_eq!(section.contours.len(), 2);
+    let area = section.measure().area;
+    let expected = 4.0 * pi * 0.15.sqrt();
+    assert!(
+        (area - expected).abs() < expected * 0.02,
+        "torus: section {area} != {expected}"
+    );
+}
+
+#[test]
+fn test_platonic_closed() {
+    for mesh in [
+        unit_tetrahedron_triangulized(),
+        unit_cube_triangulated(),
+        unit_octahedron_triangulized(),
+        unit_dodecahedron_triangulized(),
+        unit_icosahedron_triangulized(),
+    ] {
+        mesh::assert_closed_3d(&mesh);
+        assert!(mesh.measure().volume > 0.0);
+    }
+}
+
+#[test]
+fn test_curved_4d() {
+    // Name, mesh, hypervolume, offset along w and volume of the section there
+    let pi = f32::PI;
+    let shapes = [
+        (
+            "glome",
+            unit_glome(32),
+            pi * pi / 2.0,
+            0.5,
+            pi * 0.75.powf(1.5) * 4.0 / 3.0,
+        ),
+        (
+            "spherinder",
+            unit_spherinder(32),
+            pi * 8.0 / 3.0,
+            0.5,
+            pi * 4.0 / 3.0,
+        ),
+        ("cubinder", unit_cubinder(32), pi * 4.0, 0.5, pi * 2.0),
+        (
+            "duocylinder",
+            unit_duocylinder(32),
+            pi * pi,
+            0.5,
+            pi * 3.0.sqrt(),
+        ),
+        // Tube of radius 0.3 around the flat torus, cut into two tori
+        (
+            "tiger",
+            unit_tiger(32),
+            4.0 * pi * pi * 0.09 * pi * 0.49,
+            0.0,
+            2.0 * 2.0 * pi * pi * 0.7 * 0.09,
+        ),
+        // Cut into a spherical shell
+        (
+            "spheritorus",
+            unit_spheritorus(32),
+            4.0 * pi * 0.09 * pi * (0.49 + 0.0225),
+            0.0,
+            pi * (1.0 - 0.064) * 4.0 / 3.0,
+        ),
+    ];
+    // The tessellations are inscribed, so they are a bit smaller
+    let close = |measured: f32, expected: f32| measured < expected && measured > expected * 0.95;
+    for (name, mesh, volume, offset, section) in shapes {
+        mesh::assert_closed_4d(&mesh);
+        let measured = mesh::volume_4d(&mesh);
+        assert!(close(measured, volume), "{name}: {measured} != {volume}");
+
+        let measured = mesh::section_volume(&mesh, offset);
+        assert!(
+            close(measured, section),
+            "{name}: section {measured} != {section}"
+        );
+    }
+}
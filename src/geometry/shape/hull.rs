@@ -0,0 +1,347 @@
+use super::*;
+
+/// Convex hull of a point cloud in 3d.
+/// Triangles are oriented counter-clockwise when viewed from the outside,
+/// so their normals point outwards.
+///
+/// Returns no triangles if all points lie in a single plane.
+pub fn convex_hull_3d(points: &[vec3<f32>]) -> Vec<Triangle> {
+    let coords: Vec<[f64; 3]> = points
+        .iter()
+        .map(|p| [p.x, p.y, p.z].map(f64::from))
+        .collect();
+    convex_hull(&coords)
+        .into_iter()
+        .map(|facet| Triangle::new(facet.map(|i| points[i])))
+        .collect()
+}
+
+/// Convex hull of a point cloud in 4d.
+/// Tetrahedra `abcd` are oriented so that the normal
+/// `vec4_cross(b - a, c - a, d - a)` points outwards.
+///
+/// Returns no tetrahedra if all points lie in a single hyperplane.
+pub fn convex_hull_4d(points: &[vec4<f32>]) -> Vec<Tetrahedron4d> {
+    let coords: Vec<[f64; 4]> = points
+        .iter()
+        .map(|p| [p.x, p.y, p.z, p.w].map(f64::from))
+        .collect();
+    convex_hull(&coords)
+        .into_iter()
+        .map(|facet| Tetrahedron4d {
+            vertices: facet.map(|i| points[i]),
+        })
+        .collect()
+}
+
+struct Facet<const N: usize> {
+    vertices: [usize; N],
+    normal: [f64; N],
+    offset: f64,
+}
+
+/// Incremental convex hull in `N` dimensions.
+/// Returns the facets as indices into the points,
+/// oriented so that [hyperplane_normal] points outwards.
+pub(super) fn convex_hull<const N: usize>(points: &[[f64; N]]) -> Vec<[usize; N]> {
+    let scale = points
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |acc, x| acc.max(x.abs()))
+        .max(1e-9);
+    let eps = scale * 1e-5;
+
+    let Some(simplex) = initial_simplex(points, eps) else {
+        return Vec::new();
+    };
+    let mut interior = [0.0; N];
+    for &i in &simplex {
+        for (x, p) in interior.iter_mut().zip(points[i]) {
+            *x += p / simplex.len() as f64;
+        }
+    }
+
+    let make_facet = |mut vertices: [usize; N]| {
+        let mut normal = hyperplane_normal(vertices.map(|i| points[i]));
+        let len = dot(normal, normal).sqrt();
+        if len > 0.0 {
+            normal = normal.map(|x| x / len);
+        }
+        let mut offset = dot(normal, points[vertices[0]]);
+        if dot(normal, interior) > offset {
+            // Swapping two vertices flips the orientation
+            vertices.swap(0, 1);
+            normal = normal.map(|x| -x);
+            offset = -offset;
+        }
+        Facet {
+            vertices,
+            normal,
+            offset,
+        }
+    };
+
+    let mut facets: Vec<Facet<N>> = (0..simplex.len())
+        .map(|skip| {
+            let vertices: Vec<usize> = simplex
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != skip)
+                .map(|(_, &v)| v)
+                .collect();
+            make_facet(vertices.try_into().unwrap())
+        })
+        .collect();
+
+    for (point_id, &point) in points.iter().enumerate() {
+        if simplex.contains(&point_id) {
+            continue;
+        }
+
+        let (visible, hidden): (Vec<Facet<N>>, Vec<Facet<N>>) = facets
+            .into_iter()
+            .partition(|facet| dot(facet.normal, point) - facet.offset > eps);
+        facets = hidden;
+        if visible.is_empty() {
+            // The point is inside the hull
+            continue;
+        }
+
+        // Ridges of the visible region that are not shared between visible facets form the horizon
+        let mut ridges: HashMap<Vec<usize>, usize> = HashMap::new();
+        for facet in &visible {
+            for skip in 0..N {
+                let mut ridge: Vec<usize> = facet.vertices.to_vec();
+                ridge.remove(skip);
+                ridge.sort();
+                *ridges.entry(ridge).or_default() += 1;
+            }
+        }
+        for (mut ridge, count) in ridges {
+            if count == 1 {
+                ridge.push(point_id);
+                facets.push(make_facet(ridge.try_into().unwrap()));
+            }
+        }
+    }
+
+    facets.into_iter().map(|facet| facet.vertices).collect()
+}
+
+/// Groups the facets of a convex hull lying in the same hyperplane,
+/// returning the outward unit normal and the vertices of every group.
+pub(super) fn group_facets<const N: usize>(
+    points: &[[f64; N]],
+    facets: &[[usize; N]],
+) -> Vec<([f64; N], Vec<usize>)> {
+    let mut groups: Vec<([f64; N], Vec<usize>)> = Vec::new();
+    let mut group_ids: HashMap<[i64; N], usize> = HashMap::new();
+    for facet in facets {
+        let normal = hyperplane_normal(facet.map(|i| points[i]));
+        let len = dot(normal, normal).sqrt();
+        let normal = normal.map(|x| x / len);
+        // Facets of a convex hull with the same outward normal lie in the same hyperplane
+        let key = normal.map(|x| (x * 1e4).round() as i64);
+        let group = *group_ids.entry(key).or_insert_with(|| {
+            groups.push((normal, Vec::new()));
+            groups.len() - 1
+        });
+        let vertices = &mut groups[group].1;
+        for &i in facet {
+            if !vertices.contains(&i) {
+                vertices.push(i);
+            }
+        }
+    }
+    groups
+}
+
+/// Merges the coplanar triangles of a convex hull in 3d into polygons,
+/// each going counter-clockwise when viewed from the outside.
+pub(super) fn hull_polygons(points: &[[f64; 3]], triangles: &[[usize; 3]]) -> Vec<Vec<usize>> {
+    group_facets(points, triangles)
+        .into_iter()
+        .map(|(normal, mut face)| {
+            // Sort the vertices by the angle around the center of the face
+            let mut center = [0.0; 3];
+            for &i in &face {
+                for (c, x) in center.iter_mut().zip(points[i]) {
+                    *c += x / face.len() as f64;
+                }
+            }
+            let u = sub(points[face[0]], center);
+            let v = [
+                normal[1] * u[2] - normal[2] * u[1],
+                normal[2] * u[0] - normal[0] * u[2],
+                normal[0] * u[1] - normal[1] * u[0],
+            ];
+            let angle = |i: usize| {
+                let delta = sub(points[i], center);
+                dot(delta, v).atan2(dot(delta, u))
+            };
+            face.sort_by(|&a, &b| angle(a).total_cmp(&angle(b)));
+            face
+        })
+        .collect()
+}
+
+/// Picks `N + 1` affinely independent points, greedily maximizing the volume.
+fn initial_simplex<const N: usize>(points: &[[f64; N]], eps: f64) -> Option<Vec<usize>> {
+    let first = (0..points.len()).min_by(|&a, &b| points[a][0].total_cmp(&points[b][0]))?;
+    let mut simplex = vec![first];
+    let mut basis: Vec<[f64; N]> = Vec::new();
+    while simplex.len() <= N {
+        // Distance from the affine hull of the current simplex
+        let residual = |point: [f64; N]| {
+            let mut delta = sub(point, points[first]);
+            for &axis in &basis {
+                let t = dot(delta, axis);
+                delta = sub(delta, axis.map(|x| x * t));
+            }
+            delta
+        };
+        let (best, delta) = points
+            .iter()
+            .enumerate()
+            .map(|(i, &point)| (i, residual(point)))
+            .max_by(|(_, a), (_, b)| dot(*a, *a).total_cmp(&dot(*b, *b)))?;
+        let len = dot(delta, delta).sqrt();
+        if len < eps {
+            // Degenerate point cloud
+            return None;
+        }
+        simplex.push(best);
+        basis.push(delta.map(|x| x / len));
+    }
+    Some(simplex)
+}
+
+/// Normal of the hyperplane passing through `N` points in `N` dimensions,
+/// such that `dot(normal, x) = det[b - a, c - a, .., x]`.
+/// In 3d that is the usual cross product `(b - a) x (c - a)`.
+pub(super) fn hyperplane_normal<const N: usize>(vertices: [[f64; N]; N]) -> [f64; N] {
+    let rows: Vec<[f64; N]> = vertices[1..].iter().map(|&v| sub(v, vertices[0])).collect();
+    std::array::from_fn(|i| {
+        // Cofactor expansion along the last row
+        let minor: Vec<Vec<f64>> = rows
+            .iter()
+            .map(|row| (0..N).filter(|&j| j != i).map(|j| row[j]).collect())
+            .collect();
+        let sign = (-1.0_f64).powi((N - 1 + i) as i32);
+        sign * determinant(minor)
+    })
+}
+
+/// Determinant of a square matrix using gaussian elimination.
+fn determinant(mut matrix: Vec<Vec<f64>>) -> f64 {
+    let n = matrix.len();
+    let mut result = 1.0;
+    for col in 0..n {
+        let Some(pivot) =
+            (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))
+        else {
+            return 0.0;
+        };
+        if matrix[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            matrix.swap(pivot, col);
+            result = -result;
+        }
+        result *= matrix[col][col];
+        let (upper, lower) = matrix.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for row in lower {
+            let t = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= t * p;
+            }
+        }
+    }
+    result
+}
+
+fn dot<const N: usize>(a: [f64; N], b: [f64; N]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn sub<const N: usize>(a: [f64; N], b: [f64; N]) -> [f64; N] {
+    std::array::from_fn(|i| a[i] - b[i])
+}
+
+#[test]
+fn test_convex_hull_cube() {
+    let mut points: Vec<vec3<f32>> = unit_cube()
+        .into_iter()
+        .map(|(x, y, z)| vec3(x, y, z))
+        .collect();
+    points.push(vec3(0.1, 0.2, -0.3));
+    points.push(vec3(0.0, 1.0, 0.0));
+
+    let hull = convex_hull_3d(&points);
+    assert_eq!(hull.len(), 12);
+    for triangle in hull {
+        let center = triangle.vertices.into_iter().fold(vec3::ZERO, vec3::add) / 3.0;
+        assert!(vec3::dot(triangle.normal, center) > 0.0);
+    }
+}
+
+#[test]
+fn test_convex_hull_tesseract() {
+    let coord = |i: usize, bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+    let mut points: Vec<vec4<f32>> = (0..16)
+        .map(|i| vec4(coord(i, 0), coord(i, 1), coord(i, 2), coord(i, 3)))
+        .collect();
+    points.push(vec4(0.1, 0.2, -0.3, 0.4));
+    points.push(vec4(0.0, 1.0, 0.0, 0.0));
+
+    let hull = convex_hull_4d(&points);
+    assert!(!hull.is_empty());
+
+    // Closed: every triangle is shared by exactly two tetrahedra
+    let mut faces: HashMap<[[i64; 4]; 3], usize> = HashMap::new();
+    for tetrahedron in &hull {
+        let key = |v: vec4<f32>| [v.x, v.y, v.z, v.w].map(|x| (x * 1e3).round() as i64);
+        let [a, b, c, d] = tetrahedron.vertices.map(key);
+        for mut face in [[a, b, c], [a, b, d], [a, c, d], [b, c, d]] {
+            face.sort();
+            *faces.entry(face).or_default() += 1;
+        }
+    }
+    assert!(faces.values().all(|&count| count == 2));
+
+    // Volume as the sum of the cones from the origin, positive for the outward normals
+    let volume: f32 = hull
+        .iter()
+        .map(|tetrahedron| {
+            let [a, b, c, d] = tetrahedron.vertices;
+            vec4::dot(super::super::vec4_cross(b - a, c - a, d - a), a) / 24.0
+        })
+        .sum();
+    assert!((volume - 16.0).abs() < 1e-4, "volume {volume}");
+
+    // The tetrahedra make up 8 cubic cells
+    let coords: Vec<[f64; 4]> = points
+        .iter()
+        .map(|p| [p.x, p.y, p.z, p.w].map(f64::from))
+        .collect();
+    let cells = group_facets(&coords, &convex_hull(&coords));
+    assert_eq!(cells.len(), 8);
+    assert!(cells.iter().all(|(_, vertices)| vertices.len() == 8));
+}
+
+#[test]
+fn test_convex_hull_16cell() {
+    let points: Vec<vec4<f32>> = [vec4::UNIT_X, vec4::UNIT_Y, vec4::UNIT_Z, vec4::UNIT_W]
+        .into_iter()
+        .flat_map(|v| [v, -v])
+        .collect();
+    let hull = convex_hull_4d(&points);
+    assert_eq!(hull.len(), 16);
+    for tetrahedron in hull {
+        let [a, b, c, d] = tetrahedron.vertices;
+        let normal = super::super::vec4_cross(b - a, c - a, d - a);
+        assert!(vec4::dot(normal, a + b + c + d) > 0.0);
+    }
+}
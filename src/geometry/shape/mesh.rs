@@ -0,0 +1,365 @@
+use super::*;
+
+use crate::geometry::{vec4_cross, vec4_len, vec4_norm};
+
+/// Measurements of a closed [Mesh3d].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VolumeMeasurements {
+    /// Enclosed volume.
+    pub volume: f32,
+    /// Total area of the triangles.
+    pub surface_area: f32,
+    /// Center of mass of the enclosed volume.
+    pub centroid: vec3<f32>,
+}
+
+/// Triangle mesh with shared vertices.
+#[derive(Debug, Clone)]
+pub struct Mesh3d {
+    vertices: Vec<vec3<f32>>,
+    triangles: Vec<[usize; 3]>,
+    bounding_center: vec3<f32>,
+    bounding_radius: f32,
+}
+
+impl Mesh3d {
+    /// Construct a mesh from the vertices and triangles indexing into them.
+    pub fn new(vertices: Vec<vec3<f32>>, triangles: Vec<[usize; 3]>) -> Self {
+        // Sphere around the center of the bounding box
+        let (min, max) = vertices.iter().fold(
+            (vec3::splat(f32::INFINITY), vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), &v| {
+                (
+                    vec3(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+                    vec3(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+                )
+            },
+        );
+        let bounding_center = if vertices.is_empty() {
+            vec3::ZERO
+        } else {
+            (min + max) / 2.0
+        };
+        let bounding_radius = vertices
+            .iter()
+            .map(|&v| (v - bounding_center).len())
+            .fold(0.0, f32::max);
+
+        Self {
+            vertices,
+            triangles,
+            bounding_center,
+            bounding_radius,
+        }
+    }
+
+    /// Construct a mesh from a triangle soup, welding vertices at identical positions.
+    pub fn from_triangles(triangles: impl IntoIterator<Item = Triangle>) -> Self {
+        let mut vertex_ids: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut vertices = Vec::new();
+        let triangles = triangles
+            .into_iter()
+            .map(|triangle| {
+                triangle.vertices.map(|v| {
+                    *vertex_ids
+                        .entry([v.x.to_bits(), v.y.to_bits(), v.z.to_bits()])
+                        .or_insert_with(|| {
+                            vertices.push(v);
+                            vertices.len() - 1
+                        })
+                })
+            })
+            .collect();
+        Self::new(vertices, triangles)
+    }
+
+    pub fn vertices(&self) -> &[vec3<f32>] {
+        &self.vertices
+    }
+
+    /// Center and radius of a sphere containing all vertices.
+    pub fn bounding_sphere(&self) -> (vec3<f32>, f32) {
+        (self.bounding_center, self.bounding_radius)
+    }
+
+    /// Triangles as indices into [Self::vertices].
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    pub fn triangle(&self, index: usize) -> Triangle {
+        Triangle::new(self.triangles[index].map(|i| self.vertices[i]))
+    }
+
+    pub fn iter_triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+        (0..self.triangles.len()).map(|i| self.triangle(i))
+    }
+
+    /// Flat shaded triangle list to be uploaded to the gpu.
+    pub fn to_vertices(&self) -> Vec<Vertex> {
+        self.iter_triangles()
+            .flat_map(Triangle::into_vertices)
+            .collect()
+    }
+
+    /// Calculates the enclosed volume, surface area and centroid of a closed mesh.
+    pub fn measure(&self) -> VolumeMeasurements {
+        // Integrate over the tetrahedra between the origin and every triangle
+        let mut volume = 0.0;
+        let mut surface_area = 0.0;
+        let mut first = vec3::ZERO;
+        for triangle in &self.triangles {
+            let [a, b, c] = triangle.map(|i| self.vertices[i]);
+            let signed = vec3::dot(a, vec3::cross(b, c)) / 6.0;
+            volume += signed;
+            surface_area += vec3::cross(b - a, c - a).len() / 2.0;
+            first += (a + b + c) * signed / 4.0;
+        }
+        if volume.abs() < 1e-6 {
+            return VolumeMeasurements {
+                surface_area,
+                ..default()
+            };
+        }
+        VolumeMeasurements {
+            volume: volume.abs(),
+            surface_area,
+            centroid: first / volume,
+        }
+    }
+}
+
+/// Tetrahedral boundary mesh of a 4d object with shared vertices.
+/// The cells are oriented so that their normals look outwards, see [Mesh4d::cell_normal].
+#[derive(Debug, Clone)]
+pub struct Mesh4d {
+    vertices: Vec<vec4<f32>>,
+    cells: Vec<[usize; 4]>,
+    bounding_center: vec4<f32>,
+    bounding_radius: f32,
+}
+
+impl Mesh4d {
+    /// Construct a mesh from the vertices and tetrahedral cells indexing into them.
+    /// The order of the vertices in the cells may change to orient them.
+    pub fn new(vertices: Vec<vec4<f32>>, mut cells: Vec<[usize; 4]>) -> Self {
+        orient_cells(&vertices, &mut cells);
+
+        // Sphere around the center of the bounding box
+        let (min, max) = vertices.iter().fold(
+            (vec4::splat(f32::INFINITY), vec4::splat(f32::NEG_INFINITY)),
+            |(min, max), &v| {
+                (
+                    vec4(
+                        min.x.min(v.x),
+                        min.y.min(v.y),
+                        min.z.min(v.z),
+                        min.w.min(v.w),
+                    ),
+                    vec4(
+                        max.x.max(v.x),
+                        max.y.max(v.y),
+                        max.z.max(v.z),
+                        max.w.max(v.w),
+                    ),
+                )
+            },
+        );
+        let bounding_center = if vertices.is_empty() {
+            vec4::ZERO
+        } else {
+            (min + max) / 2.0
+        };
+        let bounding_radius = vertices
+            .iter()
+            .map(|&v| vec4_len(v - bounding_center))
+            .fold(0.0, f32::max);
+
+        Self {
+            vertices,
+            cells,
+            bounding_center,
+            bounding_radius,
+        }
+    }
+
+    /// Construct a mesh from separate tetrahedra, welding vertices at identical positions.
+    pub fn from_tetrahedra(tetrahedra: impl IntoIterator<Item = Tetrahedron4d>) -> Self {
+        let mut vertex_ids: HashMap<[u32; 4], usize> = HashMap::new();
+        let mut vertices = Vec::new();
+        let cells = tetrahedra
+            .into_iter()
+            .map(|tetrahedron| {
+                tetrahedron.vertices.map(|v| {
+                    *vertex_ids
+                        .entry([v.x.to_bits(), v.y.to_bits(), v.z.to_bits(), v.w.to_bits()])
+                        .or_insert_with(|| {
+                            vertices.push(v);
+                            vertices.len() - 1
+                        })
+                })
+            })
+            .collect();
+        Self::new(vertices, cells)
+    }
+
+    pub fn vertices(&self) -> &[vec4<f32>] {
+        &self.vertices
+    }
+
+    /// Center and radius of a hypersphere containing all vertices.
+    pub fn bounding_sphere(&self) -> (vec4<f32>, f32) {
+        (self.bounding_center, self.bounding_radius)
+    }
+
+    /// Tetrahedral cells as indices into [Self::vertices].
+    pub fn cells(&self) -> &[[usize; 4]] {
+        &self.cells
+    }
+
+    /// Outward unit normal of the cell.
+    pub fn cell_normal(&self, index: usize) -> vec4<f32> {
+        let [a, b, c, d] = self.cells[index].map(|i| self.vertices[i]);
+        vec4_norm(vec4_cross(b - a, c - a, d - a))
+    }
+}
+
+/// Reorders the vertices of the cells so that `vec4_cross(b - a, c - a, d - a)` looks outwards.
+///
+/// Neighbouring cells have to go around their shared face in opposite directions,
+/// which orients every connected component up to a sign.
+/// The sign is chosen so that the volume enclosed by the component is positive.
+fn orient_cells(vertices: &[vec4<f32>], cells: &mut [[usize; 4]]) {
+    let faces = |[a, b, c, d]: [usize; 4]| {
+        [[b, c, d], [a, c, d], [a, b, d], [a, b, c]].map(|mut face| {
+            face.sort();
+            face
+        })
+    };
+    let mut face_cells: HashMap<[usize; 3], Vec<usize>> = HashMap::new();
+    for (i, &cell) in cells.iter().enumerate() {
+        for face in faces(cell) {
+            face_cells.entry(face).or_default().push(i);
+        }
+    }
+
+    let mut visited = vec![false; cells.len()];
+    for start in 0..cells.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut component = vec![start];
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            for face in faces(cells[cell]) {
+                for &other in &face_cells[&face] {
+                    if visited[other] {
+                        continue;
+                    }
+                    visited[other] = true;
+                    if face_orientation(cells[other], face) == face_orientation(cells[cell], face) {
+                        cells[other].swap(0, 1);
+                    }
+                    component.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+
+        // Divergence theorem: the volume is the sum of `dot(x, normal)` over the boundary
+        let volume: f32 = component
+            .iter()
+            .map(|&i| {
+                let [a, b, c, d] = cells[i].map(|v| vertices[v]);
+                vec4::dot(a, vec4_cross(b - a, c - a, d - a))
+            })
+            .sum();
+        if volume < 0.0 {
+            for &i in &component {
+                cells[i].swap(0, 1);
+            }
+        }
+    }
+}
+
+/// Orientation of the face (given by sorted vertices) induced by the cell containing it,
+/// i.e. the sign of the face in the boundary of the cell.
+fn face_orientation(cell: [usize; 4], face: [usize; 3]) -> bool {
+    let skipped = cell.iter().position(|v| !face.contains(v)).unwrap_or(0);
+    let rest: Vec<usize> = cell.iter().copied().filter(|v| face.contains(v)).collect();
+    let inversions = (0..rest.len())
+        .flat_map(|i| (i + 1..rest.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| rest[i] > rest[j])
+        .count();
+    (skipped + inversions) % 2 == 0
+}
+
+/// Asserts that every edge of the mesh is shared by exactly two triangles going opposite ways.
+#[cfg(test)]
+pub(super) fn assert_closed_3d(mesh: &Mesh3d) {
+    let mut edges: HashMap<[usize; 2], usize> = HashMap::new();
+    for &[a, b, c] in mesh.triangles() {
+        for edge in [[a, b], [b, c], [c, a]] {
+            *edges.entry(edge).or_default() += 1;
+        }
+    }
+    for (&[a, b], &count) in &edges {
+        let back = edges.get(&[b, a]).copied().unwrap_or(0);
+        assert!(
+            count == 1 && back == 1,
+            "edge {a} {b} is used {count} times and {back} times backwards"
+        );
+    }
+}
+
+/// Asserts that every face of the mesh is shared by exactly two cells
+/// which induce opposite orientations on it.
+#[cfg(test)]
+pub(super) fn assert_closed_4d(mesh: &Mesh4d) {
+    let mut faces: HashMap<[usize; 3], Vec<bool>> = HashMap::new();
+    for &cell in mesh.cells() {
+        for skip in cell {
+            let mut face = [0; 3];
+            for (f, v) in face.iter_mut().zip(cell.into_iter().filter(|&v| v != skip)) {
+                *f = v;
+            }
+            face.sort();
+            faces
+                .entry(face)
+                .or_default()
+                .push(face_orientation(cell, face));
+        }
+    }
+    for (face, orientations) in &faces {
+        assert!(
+            matches!(orientations[..], [a, b] if a != b),
+            "face {face:?} has orientations {orientations:?}"
+        );
+    }
+}
+
+/// Hypervolume enclosed by a closed mesh with the outward oriented cells.
+#[cfg(test)]
+pub(super) fn volume_4d(mesh: &Mesh4d) -> f32 {
+    mesh.cells()
+        .iter()
+        .map(|cell| {
+            let [a, b, c, d] = cell.map(|i| mesh.vertices()[i]);
+            vec4::dot(a, vec4_cross(b - a, c - a, d - a)) / 24.0
+        })
+        .sum()
+}
+
+/// Slices the mesh by the space `w = offset`,
+/// asserting that the section is closed, and returns the volume of the section.
+#[cfg(test)]
+pub(super) fn section_volume(mesh: &Mesh4d, offset: f32) -> f32 {
+    let space = crate::geometry::space::Space {
+        normal: vec4::UNIT_W,
+        offset,
+    };
+    let section = space.cross_sect(mesh, crate::geometry::mat5::identity());
+    assert_closed_3d(&section);
+    section.measure().volume
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+use crate::geometry::{hyperplane::SimplexMesh, VecN};
+
+/// Filled regular polygon with `n` sides and the circumradius of 1,
+/// split into triangles around the center.
+pub fn unit_polygon_filled(n: usize) -> SimplexMesh<2> {
+    assert!(n >= 3, "polygon must have at least 3 sides");
+    let vertices = unit_polygon(n)
+        .into_iter()
+        .map(|(x, y)| VecN([x, y]))
+        .collect();
+    fan(vertices)
+}
+
+/// Filled star with `points` spikes of the circumradius of 1,
+/// the inner vertices are at the distance of `inner_radius` from the center.
+pub fn unit_star(points: usize, inner_radius: f32) -> SimplexMesh<2> {
+    assert!(points >= 2, "star must have at least 2 points");
+    let vertices = (0..points * 2)
+        .map(|i| {
+            let angle = Angle::from_radians(i as f32 * f32::PI / points as f32 + f32::PI / 2.0);
+            let (sin, cos) = angle.sin_cos();
+            let radius = if i % 2 == 0 { 1.0 } else { inner_radius };
+            VecN([cos * radius, sin * radius])
+        })
+        .collect();
+    fan(vertices)
+}
+
+/// Triangulates a polygon, star-shaped around the origin, with triangles sharing the origin.
+fn fan(contour: Vec<VecN<2>>) -> SimplexMesh<2> {
+    let n = contour.len();
+    let mut vertices = vec![VecN::ZERO];
+    vertices.extend(contour);
+    let simplices = (0..n).map(|i| vec![0, i + 1, (i + 1) % n + 1]).collect();
+    SimplexMesh {
+        vertices,
+        simplices,
+    }
+}
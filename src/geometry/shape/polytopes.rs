@@ -0,0 +1,293 @@
+use super::*;
+
+use super::hull::{convex_hull, hull_polygons};
+
+use crate::geometry::{vec4_len, vec4_norm};
+
+const PHI: f32 = 1.618_034; // Golden ratio
+
+/// Tesseract (8-cell) with the circumradius of 2.
+pub fn unit_tesseract() -> Mesh4d {
+    let vertices: Vec<vec4<f32>> = unit_cube()
+        .into_iter()
+        .flat_map(|(x, y, z)| [-1.0, 1.0].map(|w| vec4(x, y, z, w)))
+        .collect();
+    let cells = axes4d().flat_map(|axis| [axis, -axis]);
+    polytope_from_cells(vertices, cells)
+}
+
+/// 16-cell with the circumradius of 2.
+pub fn unit_16cell() -> Mesh4d {
+    let vertices: Vec<vec4<f32>> = axes4d()
+        .flat_map(|axis| [axis * 2.0, -axis * 2.0])
+        .collect();
+    let cells = unit_cube()
+        .into_iter()
+        .flat_map(|(x, y, z)| [-1.0, 1.0].map(|w| vec4(x, y, z, w)));
+    polytope_from_cells(vertices, cells)
+}
+
+/// 24-cell with the circumradius of 2.
+pub fn unit_24cell() -> Mesh4d {
+    let mut vertices = Vec::new();
+    for i in 0..4 {
+        for j in i + 1..4 {
+            for (a, b) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)] {
+                let mut v = [0.0; 4];
+                v[i] = a;
+                v[j] = b;
+                vertices.push(vec4(v[0], v[1], v[2], v[3]) * 2.0.sqrt());
+            }
+        }
+    }
+    let cells = axes4d().flat_map(|axis| [axis, -axis]).chain(
+        unit_cube()
+            .into_iter()
+            .flat_map(|(x, y, z)| [-1.0, 1.0].map(|w| vec4(x, y, z, w))),
+    );
+    polytope_from_cells(vertices, cells)
+}
+
+/// Vertices of the 600-cell with the circumradius of 1.
+fn vertices_600cell() -> Vec<vec4<f32>> {
+    let mut vertices: Vec<vec4<f32>> = axes4d().flat_map(|axis| [axis, -axis]).collect();
+    vertices.extend(
+        unit_cube()
+            .into_iter()
+            .flat_map(|(x, y, z)| [-1.0, 1.0].map(|w| vec4(x, y, z, w) * 0.5)),
+    );
+
+    // Even permutations of (±phi, ±1, ±1/phi, 0) / 2
+    let even_permutations = [
+        [0, 1, 2, 3],
+        [0, 2, 3, 1],
+        [0, 3, 1, 2],
+        [1, 0, 3, 2],
+        [1, 2, 0, 3],
+        [1, 3, 2, 0],
+        [2, 0, 1, 3],
+        [2, 1, 3, 0],
+        [2, 3, 0, 1],
+        [3, 0, 2, 1],
+        [3, 1, 0, 2],
+        [3, 2, 1, 0],
+    ];
+    for (x, y, z) in unit_cube() {
+        let values = [x * PHI, y, z / PHI, 0.0];
+        for permutation in even_permutations {
+            let v = permutation.map(|i| values[i] * 0.5);
+            vertices.push(vec4(v[0], v[1], v[2], v[3]));
+        }
+    }
+    vertices
+}
+
+/// Cells of the 600-cell as quadruples of mutually adjacent vertices.
+fn cells_600cell(vertices: &[vec4<f32>]) -> Vec<[usize; 4]> {
+    let edge_len = 1.0 / PHI;
+    let adjacent =
+        |a: usize, b: usize| (vec4_len(vertices[a] - vertices[b]) - edge_len).abs() < 1e-3;
+    let neighbours: Vec<Vec<usize>> = (0..vertices.len())
+        .map(|a| {
+            (a + 1..vertices.len())
+                .filter(|&b| adjacent(a, b))
+                .collect()
+        })
+        .collect();
+
+    let mut cells = Vec::new();
+    for a in 0..vertices.len() {
+        for &b in &neighbours[a] {
+            for &c in &neighbours[b] {
+                if !adjacent(a, c) {
+                    continue;
+                }
+                for &d in &neighbours[c] {
+                    if adjacent(a, d) && adjacent(b, d) {
+                        cells.push([a, b, c, d]);
+                    }
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// 600-cell with the circumradius of 2.
+pub fn unit_600cell() -> Mesh4d {
+    let vertices = vertices_600cell();
+    let cells = cells_600cell(&vertices);
+    Mesh4d::new(vertices.into_iter().map(|v| v * 2.0).collect(), cells)
+}
+
+/// 120-cell with the circumradius of 2.
+pub fn unit_120cell() -> Mesh4d {
+    // Constructed as the dual of the 600-cell
+    let dual_vertices = vertices_600cell();
+    let vertices: Vec<vec4<f32>> = cells_600cell(&dual_vertices)
+        .into_iter()
+        .map(|cell| {
+            vec4_norm(
+                cell.into_iter()
+                    .map(|i| dual_vertices[i])
+                    .fold(vec4::ZERO, vec4::add),
+            ) * 2.0
+        })
+        .collect();
+    polytope_from_cells(vertices, dual_vertices)
+}
+
+fn axes4d() -> impl Iterator<Item = vec4<f32>> {
+    [vec4::UNIT_X, vec4::UNIT_Y, vec4::UNIT_Z, vec4::UNIT_W].into_iter()
+}
+
+/// Constructs the boundary of a convex polytope given its vertices and the directions
+/// towards the centers of its cells.
+pub(super) fn polytope_from_cells(
+    vertices: Vec<vec4<f32>>,
+    cell_directions: impl IntoIterator<Item = vec4<f32>>,
+) -> Mesh4d {
+    let cells = cell_directions
+        .into_iter()
+        .flat_map(|direction| {
+            // The cell consists of the vertices furthest in that direction
+            let max = vertices
+                .iter()
+                .map(|&v| r32(vec4::dot(v, direction)))
+                .max()
+                .unwrap_or(r32(0.0))
+                .as_f32();
+            let cell: Vec<usize> = (0..vertices.len())
+                .filter(|&i| vec4::dot(vertices[i], direction) > max - 1e-3)
+                .collect();
+            pull_cell(&cell_faces(&vertices, &cell))
+        })
+        .collect();
+    Mesh4d::new(vertices, cells)
+}
+
+/// Finds the polygonal faces of a convex 3d cell lying in 4d space.
+/// Each face is returned as a cyclic sequence of vertices.
+pub(super) fn cell_faces(vertices: &[vec4<f32>], cell: &[usize]) -> Vec<Vec<usize>> {
+    let Some(&first) = cell.first() else {
+        return Vec::new();
+    };
+
+    // Orthonormal basis of the hyperplane containing the cell
+    let origin = vertices[first];
+    let mut basis: Vec<vec4<f32>> = Vec::with_capacity(3);
+    for &i in cell {
+        let delta = basis.iter().fold(vertices[i] - origin, |delta, &axis| {
+            delta - axis * vec4::dot(delta, axis)
+        });
+        if basis.len() < 3 && vec4_len(delta) > 1e-3 {
+            basis.push(vec4_norm(delta));
+        }
+    }
+    if basis.len() < 3 {
+        return Vec::new();
+    }
+
+    // The faces of the cell are the faces of its convex hull in that hyperplane
+    let points: Vec<[f64; 3]> = cell
+        .iter()
+        .map(|&i| {
+            let delta = vertices[i] - origin;
+            [0, 1, 2].map(|axis| f64::from(vec4::dot(delta, basis[axis])))
+        })
+        .collect();
+    hull_polygons(&points, &convex_hull(&points))
+        .into_iter()
+        .map(|face| face.into_iter().map(|i| cell[i]).collect())
+        .collect()
+}
+
+/// Splits a convex cell into tetrahedra by connecting its lowest index vertex
+/// to the triangulated faces not containing it, each face being fanned from its lowest index vertex.
+/// Since the triangulation of a face depends only on its vertices,
+/// neighbouring cells always split their shared face the same way.
+pub(super) fn pull_cell(faces: &[Vec<usize>]) -> Vec<[usize; 4]> {
+    let Some(apex) = faces.iter().flatten().copied().min() else {
+        return Vec::new();
+    };
+    faces
+        .iter()
+        .filter(|face| !face.contains(&apex))
+        .flat_map(|face| {
+            let start = (0..face.len()).min_by_key(|&i| face[i]).unwrap_or(0);
+            let face: Vec<usize> = face[start..]
+                .iter()
+                .chain(&face[..start])
+                .copied()
+                .collect();
+            (1..face.len().saturating_sub(1)).map(move |i| [apex, face[0], face[i], face[i + 1]])
+        })
+        .collect()
+}
+
+#[test]
+fn test_polytopes() {
+    // Name, polytope, vertices, cells, hypervolume.
+    // The 600-cell has the edge of 2 / phi and the 120-cell of sqrt(2) / phi^2,
+    // the dodecahedral cells of the latter are split into 27 tetrahedra each
+    let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let polytopes = [
+        ("tesseract", unit_tesseract(), 16, None, Some(16.0)),
+        ("16-cell", unit_16cell(), 8, Some(16), Some(32.0 / 3.0)),
+        ("24-cell", unit_24cell(), 24, None, Some(32.0)),
+        (
+            "600-cell",
+            unit_600cell(),
+            120,
+            Some(600),
+            Some(100.0 * (2.0 + 5.0_f32.sqrt()) / phi.powi(4)),
+        ),
+        (
+            "120-cell",
+            unit_120cell(),
+            600,
+            Some(120 * 27),
+            Some(15.0 * (105.0 + 47.0 * 5.0_f32.sqrt()) / phi.powi(8)),
+        ),
+    ];
+    for (name, mesh, vertices, cells, volume) in polytopes {
+        assert_eq!(mesh.vertices().len(), vertices, "{name}");
+        if let Some(cells) = cells {
+            assert_eq!(mesh.cells().len(), cells, "{name}");
+        }
+        for &v in mesh.vertices() {
+            assert!((vec4_len(v) - 2.0).abs() < 1e-3, "{name}: {v:?}");
+        }
+        mesh::assert_closed_4d(&mesh);
+        if let Some(expected) = volume {
+            let volume = mesh::volume_4d(&mesh);
+            assert!((volume - expected).abs() < 1e-3, "{name}: {volume}");
+        }
+    }
+}
+
+#[test]
+fn test_cell_faces() {
+    // Dodecahedral cell of the 120-cell
+    let vertices: Vec<vec4<f32>> = unit_120cell().vertices().to_vec();
+    let direction = vertices_600cell()[0];
+    let max = vertices
+        .iter()
+        .map(|&v| vec4::dot(v, direction))
+        .fold(f32::NEG_INFINITY, f32::max);
+    let cell: Vec<usize> = (0..vertices.len())
+        .filter(|&i| vec4::dot(vertices[i], direction) > max - 1e-3)
+        .collect();
+    assert_eq!(cell.len(), 20);
+
+    let faces = cell_faces(&vertices, &cell);
+    assert_eq!(faces.len(), 12);
+    for face in &faces {
+        assert_eq!(face.len(), 5);
+        // Consecutive vertices are joined by the edges of the same length
+        let edge = |i: usize| vec4_len(vertices[face[i]] - vertices[face[(i + 1) % 5]]);
+        for i in 0..5 {
+            assert!((edge(i) - edge(0)).abs() < 1e-3, "{face:?}");
+        }
+    }
+}
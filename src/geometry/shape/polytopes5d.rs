@@ -0,0 +1,149 @@
+use crate::geometry::{hyperplane::SimplexMesh, VecN};
+
+/// 5-simplex with the circumradius of 2.
+/// The boundary consists of 6 regular 5-cells.
+pub fn unit_5simplex() -> SimplexMesh<5> {
+    // Unit vectors along the axes and one more point at the same distance from all of them
+    let last = (1.0 - 6.0_f32.sqrt()) / 5.0;
+    let mut vertices: Vec<VecN<5>> = (0..5).map(VecN::unit).collect();
+    vertices.push(VecN([last; 5]));
+
+    let center = vertices.iter().fold(VecN::ZERO, |acc, &v| acc + v) / 6.0;
+    let radius = (vertices[0] - center).len();
+    let vertices = vertices
+        .into_iter()
+        .map(|v| (v - center) * (2.0 / radius))
+        .collect();
+
+    // Every facet misses one of the vertices
+    let simplices = (0..6)
+        .map(|skip| (0..6).filter(|&i| i != skip).collect())
+        .collect();
+    SimplexMesh {
+        vertices,
+        simplices,
+    }
+}
+
+/// 5-cube (penteract) with the side of 2.
+/// Each of the 10 tesseract facets is split into 24 simplices.
+pub fn unit_5cube() -> SimplexMesh<5> {
+    // The vertex index is the bit mask of its positive coordinates
+    let vertices = (0..32)
+        .map(|mask: usize| {
+            VecN(std::array::from_fn(|i| {
+                if mask >> i & 1 == 1 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }))
+        })
+        .collect();
+
+    let mut simplices = Vec::new();
+    for axis in 0..5 {
+        for side in [0, 1 << axis] {
+            // Kuhn triangulation of the facet: paths from the lowest corner to the highest one,
+            // flipping the rest of the coordinates one at a time
+            let others: Vec<usize> = (0..5).filter(|&i| i != axis).collect();
+            for order in permutations(&others) {
+                let mut corner = side;
+                let mut simplex = vec![corner];
+                for i in order {
+                    corner |= 1 << i;
+                    simplex.push(corner);
+                }
+                simplices.push(simplex);
+            }
+        }
+    }
+    SimplexMesh {
+        vertices,
+        simplices,
+    }
+}
+
+/// 5-orthoplex (pentacross) with the circumradius of 2.
+/// The boundary consists of 32 regular 5-cells, one in each orthant.
+pub fn unit_5orthoplex() -> SimplexMesh<5> {
+    // Vertex `2 * i` is on the positive side of the axis `i`, and `2 * i + 1` is on the negative
+    let vertices = (0..5)
+        .flat_map(|i| [VecN::unit(i) * 2.0, VecN::unit(i) * -2.0])
+        .collect();
+    let simplices = (0..32_usize)
+        .map(|signs| (0..5).map(|i| 2 * i + (signs >> i & 1)).collect())
+        .collect();
+    SimplexMesh {
+        vertices,
+        simplices,
+    }
+}
+
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            permutations(&rest).into_iter().map(move |mut perm| {
+                perm.insert(0, first);
+                perm
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_polytopes_5d() {
+    use super::mesh::{self, Mesh4d};
+    use crate::geometry::hyperplane::Hyperplane;
+
+    // Name, polytope, vertices, facet simplices and the volume of the section at `x4 = 0.3`
+    let polytopes = [
+        ("5-simplex", unit_5simplex(), 6, 6, None),
+        ("5-cube", unit_5cube(), 32, 10 * 24, Some(16.0)),
+        // Regular 16-cell with the circumradius of 1.7
+        (
+            "5-orthoplex",
+            unit_5orthoplex(),
+            10,
+            32,
+            Some(1.7_f32.powi(4) * 2.0 / 3.0),
+        ),
+    ];
+    for (name, polytope, vertices, facets, volume) in polytopes {
+        assert_eq!(polytope.vertices.len(), vertices, "{name}");
+        assert_eq!(polytope.simplices.len(), facets, "{name}");
+        assert!(polytope.simplices.iter().all(|simplex| simplex.len() == 5));
+
+        let hyperplane = Hyperplane {
+            normal: VecN::unit(4),
+            offset: 0.3,
+        };
+        let section = hyperplane.slice(&polytope);
+        let project = hyperplane.local_projection::<4>();
+        let section = Mesh4d::new(
+            section
+                .vertices
+                .iter()
+                .map(|&v| project(v).into())
+                .collect(),
+            section
+                .simplices
+                .iter()
+                .map(|simplex| [simplex[0], simplex[1], simplex[2], simplex[3]])
+                .collect(),
+        );
+        mesh::assert_closed_4d(&section);
+        if let Some(volume) = volume {
+            let measured = mesh::volume_4d(&section);
+            assert!(
+                (measured - volume).abs() < 1e-3,
+                "{name}: {measured} != {volume}"
+            );
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::*;
+
+use super::polytopes::pull_cell;
+
+/// Vertices of a regular polygon with `n` sides and the circumradius of 1.
+pub fn unit_polygon(n: usize) -> Vec<(f32, f32)> {
+    (0..n)
+        .map(|i| {
+            let angle = Angle::from_radians((2 * i + 1) as f32 * f32::PI / n as f32);
+            let (sin, cos) = angle.sin_cos();
+            (cos, sin)
+        })
+        .collect()
+}
+
+/// Duoprism {p}×{q}, the cartesian product of two regular polygons,
+/// one in the xy plane and the other in the zw plane, with the circumradius of 2.
+/// The {4}×{4} duoprism is the tesseract.
+pub fn unit_duoprism(p: usize, q: usize) -> Mesh4d {
+    duoprism(p, q, 2.0.sqrt())
+}
+
+/// Duoprism {p}×{q} with both polygons of the given circumradius.
+pub(super) fn duoprism(p: usize, q: usize, radius: f32) -> Mesh4d {
+    assert!(p >= 3 && q >= 3, "polygons must have at least 3 sides");
+
+    let vertices: Vec<vec4<f32>> = unit_polygon(p)
+        .into_iter()
+        .flat_map(|(x, y)| {
+            unit_polygon(q)
+                .into_iter()
+                .map(move |(z, w)| vec4(x, y, z, w) * radius)
+        })
+        .collect();
+    let index = |i: usize, j: usize| (i % p) * q + j % q;
+
+    // Every edge of one polygon times the whole other polygon gives a prism cell
+    let mut cells = Vec::new();
+    for i in 0..p {
+        let mut faces: Vec<Vec<usize>> = vec![
+            (0..q).map(|j| index(i, j)).collect(),
+            (0..q).map(|j| index(i + 1, j)).collect(),
+        ];
+        faces.extend((0..q).map(|j| {
+            vec![
+                index(i, j),
+                index(i + 1, j),
+                index(i + 1, j + 1),
+                index(i, j + 1),
+            ]
+        }));
+        cells.extend(pull_cell(&faces));
+    }
+    for j in 0..q {
+        let mut faces: Vec<Vec<usize>> = vec![
+            (0..p).map(|i| index(i, j)).collect(),
+            (0..p).map(|i| index(i, j + 1)).collect(),
+        ];
+        faces.extend((0..p).map(|i| {
+            vec![
+                index(i, j),
+                index(i + 1, j),
+                index(i + 1, j + 1),
+                index(i, j + 1),
+            ]
+        }));
+        cells.extend(pull_cell(&faces));
+    }
+
+    Mesh4d::new(vertices, cells)
+}
+
+/// Prism extruding a 3d mesh along the w axis from -1 to 1.
+/// The prism of [unit_cube_triangulated] is the tesseract.
+///
+/// The caps are split into tetrahedra around the center of the mesh,
+/// so the mesh has to be closed and star-shaped relative to its center (e.g. convex).
+pub fn unit_prism(mesh: &Mesh3d) -> Mesh4d {
+    let n = mesh.vertices().len();
+    let center = mesh.vertices().iter().copied().fold(vec3::ZERO, vec3::add) / n.max(1) as f32;
+
+    let vertices: Vec<vec4<f32>> = [-1.0, 1.0]
+        .into_iter()
+        .flat_map(|w| {
+            mesh.vertices()
+                .iter()
+                .map(move |v| v.extend(w))
+                .chain([center.extend(w)])
+        })
+        .collect();
+    let (bottom, top) = (|i: usize| i, |i: usize| n + 1 + i);
+    let (bottom_center, top_center) = (n, 2 * n + 1);
+
+    let mut cells = Vec::new();
+    for &[a, b, c] in mesh.triangles() {
+        // Caps
+        cells.push([bottom_center, bottom(a), bottom(b), bottom(c)]);
+        cells.push([top_center, top(a), top(b), top(c)]);
+
+        // Triangle times the segment gives a triangular prism cell
+        let faces = [
+            vec![bottom(a), bottom(b), bottom(c)],
+            vec![top(a), top(b), top(c)],
+            vec![bottom(a), bottom(b), top(b), top(a)],
+            vec![bottom(b), bottom(c), top(c), top(b)],
+            vec![bottom(c), bottom(a), top(a), top(c)],
+        ];
+        cells.extend(pull_cell(&faces));
+    }
+
+    Mesh4d::new(vertices, cells)
+}
+
+#[test]
+fn test_duoprism() {
+    // Area of a regular polygon with the circumradius of sqrt(2)
+    let area = |n: usize| n as f32 * (2.0 * f32::PI / n as f32).sin();
+    for (p, q) in [(3, 4), (4, 4), (6, 4), (3, 5), (5, 7)] {
+        let mesh = unit_duoprism(p, q);
+        assert_eq!(mesh.vertices().len(), p * q);
+        mesh::assert_closed_4d(&mesh);
+
+        let volume = mesh::volume_4d(&mesh);
+        let expected = area(p) * area(q);
+        assert!(
+            (volume - expected).abs() < 1e-3,
+            "{p}-{q}: {volume} != {expected}"
+        );
+
+        let volume = mesh::section_volume(&mesh, 0.1);
+        if q == 4 {
+            // The square is cut along its side of 2
+            let expected = area(p) * 2.0;
+            assert!(
+                (volume - expected).abs() < 1e-3,
+                "{p}-{q}: section {volume} != {expected}"
+            );
+        } else {
+            assert!(volume > 0.0, "{p}-{q}: section {volume}");
+        }
+    }
+}
+
+#[test]
+fn test_prism() {
+    for (name, base) in [
+        ("cube", unit_cube_triangulated()),
+        ("tetrahedron", unit_tetrahedron_triangulized()),
+        ("icosahedron", unit_icosahedron_triangulized()),
+    ] {
+        let base_volume = base.measure().volume;
+        let mesh = unit_prism(&base);
+        assert_eq!(mesh.vertices().len(), (base.vertices().len() + 1) * 2);
+        mesh::assert_closed_4d(&mesh);
+
+        let volume = mesh::volume_4d(&mesh);
+        assert!(
+            (volume - base_volume * 2.0).abs() < 1e-3,
+            "{name}: {volume}"
+        );
+
+        // Every section between the caps is the base itself
+        let volume = mesh::section_volume(&mesh, 0.3);
+        assert!(
+            (volume - base_volume).abs() < 1e-3,
+            "{name}: section {volume}"
+        );
+    }
+}
@@ -30,7 +30,7 @@ pub fn unit_cube() -> [(f32, f32, f32); 8] {
     ])
 }
 
-pub fn unit_cube_triangulated() -> Vec<Vertex> {
+pub fn unit_cube_triangulated() -> Mesh3d {
     let vertices = unit_cube().map(|(x, y, z)| vec3(x, y, z));
 
     let triangles = [
@@ -47,10 +47,7 @@ pub fn unit_cube_triangulated() -> Vec<Vertex> {
         [4, 7, 6],
         [4, 5, 7],
     ];
-    triangles
-        .into_iter()
-        .flat_map(|ids| Triangle::new(ids.map(|i| vertices[i])).into_vertices())
-        .collect()
+    Mesh3d::new(vertices.to_vec(), triangles.to_vec())
 }
 
 pub fn unit_triangle() -> [(f32, f32); 3] {
@@ -74,19 +71,11 @@ pub fn unit_tetrahedron() -> [(f32, f32, f32); 4] {
     ]
 }
 
-pub fn unit_tetrahedron_triangulized() -> Vec<Vertex> {
-    let tri = |vs: [(f32, f32, f32); 3]| Triangle::new(vs.map(|(x, y, z)| vec3(x, y, z)));
-    let [a, b, c, d] = unit_tetrahedron();
-    let triangles = [
-        tri([a, c, b]),
-        tri([a, b, d]),
-        tri([a, c, d]),
-        tri([b, c, d]),
-    ];
-    triangles
-        .into_iter()
-        .flat_map(Triangle::into_vertices)
-        .collect()
+pub fn unit_tetrahedron_triangulized() -> Mesh3d {
+    let vertices = unit_tetrahedron().map(|(x, y, z)| vec3(x, y, z));
+    let (a, b, c, d) = (0, 1, 2, 3);
+    let triangles = [[a, b, c], [a, d, b], [a, c, d], [b, d, c]];
+    Mesh3d::new(vertices.to_vec(), triangles.to_vec())
 }
 
 pub fn unit_icosahedron() -> [(f32, f32, f32); 12] {
@@ -107,9 +96,8 @@ pub fn unit_icosahedron() -> [(f32, f32, f32); 12] {
     ]
 }
 
-pub fn unit_icosahedron_triangulized() -> Vec<Vertex> {
-    let tri = |vs: [(f32, f32, f32); 3]| Triangle::new(vs.map(|(x, y, z)| vec3(x, y, z)));
-    let vs = unit_icosahedron();
+pub fn unit_icosahedron_triangulized() -> Mesh3d {
+    let vertices = unit_icosahedron().map(|(x, y, z)| vec3(x, y, z));
     // a-jbief:  0 -  9  1  8  4  5
     // d-ghlck:  3 -  6  7 11  2 10
     // b-jhgi :  1 -  9  7  6  8
@@ -138,11 +126,49 @@ pub fn unit_icosahedron_triangulized() -> Vec<Vertex> {
         (10, 8, 6),
         (4, 2, 5),
     ];
-    triangles
+    Mesh3d::new(
+        vertices.to_vec(),
+        triangles.into_iter().map(|(a, b, c)| [a, b, c]).collect(),
+    )
+}
+
+pub fn unit_octahedron() -> [(f32, f32, f32); 6] {
+    // Same circumradius as the cube
+    let r = 3.0.sqrt();
+    [
+        (r, 0.0, 0.0),
+        (-r, 0.0, 0.0),
+        (0.0, r, 0.0),
+        (0.0, -r, 0.0),
+        (0.0, 0.0, r),
+        (0.0, 0.0, -r),
+    ]
+}
+
+pub fn unit_octahedron_triangulized() -> Mesh3d {
+    let vertices = unit_octahedron().map(|(x, y, z)| vec3(x, y, z));
+    Mesh3d::from_triangles(convex_hull_3d(&vertices))
+}
+
+pub fn unit_dodecahedron() -> [(f32, f32, f32); 20] {
+    const PHI: f32 = 1.618_034; // Golden ratio
+    let mut vertices = [(0.0, 0.0, 0.0); 20];
+    vertices[..8].copy_from_slice(&unit_cube());
+    for (i, (a, b)) in [(-1.0, -1.0), (-1.0, 1.0), (1.0, -1.0), (1.0, 1.0)]
         .into_iter()
-        .map(|(a, b, c)| tri([vs[a], vs[b], vs[c]]))
-        .flat_map(Triangle::into_vertices)
-        .collect()
+        .enumerate()
+    {
+        let (a, b) = (a / PHI, b * PHI);
+        vertices[8 + i] = (0.0, a, b);
+        vertices[12 + i] = (a, b, 0.0);
+        vertices[16 + i] = (b, 0.0, a);
+    }
+    vertices
+}
+
+pub fn unit_dodecahedron_triangulized() -> Mesh3d {
+    let vertices = unit_dodecahedron().map(|(x, y, z)| vec3(x, y, z));
+    Mesh3d::from_triangles(convex_hull_3d(&vertices))
 }
 
 pub fn unit_5cell() -> [(f32, f32, f32, f32); 5] {
@@ -154,16 +180,15 @@ pub fn unit_5cell() -> [(f32, f32, f32, f32); 5] {
     [a, b, c, d, (0.0, 0.0, 0.0, 4.0 / root_five)]
 }
 
-pub fn unit_5cell_tetrahedralized() -> [Tetrahedron4d; 5] {
-    let tetra = |vs: [(f32, f32, f32, f32); 4]| Tetrahedron4d {
-        vertices: vs.map(|(x, y, z, w)| vec4(x, y, z, w)),
-    };
-    let [a, b, c, d, e] = unit_5cell();
-    [
-        tetra([a, b, c, d]),
-        tetra([a, b, c, e]),
-        tetra([a, b, d, e]),
-        tetra([a, c, d, e]),
-        tetra([b, c, d, e]),
-    ]
+pub fn unit_5cell_tetrahedralized() -> Mesh4d {
+    let vertices = unit_5cell().map(|(x, y, z, w)| vec4(x, y, z, w));
+    let (a, b, c, d, e) = (0, 1, 2, 3, 4);
+    let cells = [
+        [a, b, c, d],
+        [a, b, c, e],
+        [a, b, d, e],
+        [a, c, d, e],
+        [b, c, d, e],
+    ];
+    Mesh4d::new(vertices.to_vec(), cells.to_vec())
 }
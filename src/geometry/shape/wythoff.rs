@@ -0,0 +1,227 @@
+use super::*;
+
+use super::hull::convex_hull;
+
+/// Finite Coxeter groups with linear Coxeter-Dynkin diagrams,
+/// and the products of two dihedral groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoxeterGroup {
+    /// Tetrahedral symmetry, [3,3].
+    A3,
+    /// Octahedral symmetry, [4,3].
+    B3,
+    /// Icosahedral symmetry, [5,3].
+    H3,
+    /// 5-cell symmetry, [3,3,3].
+    A4,
+    /// Tesseract symmetry, [4,3,3].
+    B4,
+    /// 24-cell symmetry, [3,4,3].
+    F4,
+    /// 120-cell symmetry, [5,3,3].
+    H4,
+    /// Duoprism symmetry, [p,2,q], with `p` and `q` at least 2.
+    Duoprism(usize, usize),
+}
+
+impl CoxeterGroup {
+    /// Number of mirrors (nodes in the diagram), equal to the dimension of the space.
+    pub fn rank(&self) -> usize {
+        match self {
+            Self::A3 | Self::B3 | Self::H3 => 3,
+            Self::A4 | Self::B4 | Self::F4 | Self::H4 | Self::Duoprism(..) => 4,
+        }
+    }
+
+    /// Coxeter matrix: `m[i][j]` is the order of the rotation
+    /// generated by the mirrors `i` and `j`, so the angle between them is `pi / m[i][j]`.
+    pub fn matrix(&self) -> Vec<Vec<usize>> {
+        let links = match *self {
+            Self::A3 => vec![3, 3],
+            Self::B3 => vec![4, 3],
+            Self::H3 => vec![5, 3],
+            Self::A4 => vec![3, 3, 3],
+            Self::B4 => vec![4, 3, 3],
+            Self::F4 => vec![3, 4, 3],
+            Self::H4 => vec![5, 3, 3],
+            Self::Duoprism(p, q) => {
+                // A single mirror or none would make the Gram matrix singular
+                assert!(
+                    p >= 2 && q >= 2,
+                    "duoprism polygons must have at least 2 sides"
+                );
+                vec![p, 2, q]
+            }
+        };
+        let n = links.len() + 1;
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match i.abs_diff(j) {
+                        0 => 1,
+                        1 => links[i.min(j)],
+                        _ => 2, // Unconnected nodes are perpendicular
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Uniform polyhedron given by the Wythoff construction with the circumradius of 2.
+/// `rings[i]` tells whether the node `i` of the diagram is ringed,
+/// e.g. `[true, true, false]` in [CoxeterGroup::H3] is the truncated dodecahedron.
+pub fn wythoff_polyhedron(group: CoxeterGroup, rings: [bool; 3]) -> Mesh3d {
+    assert_eq!(group.rank(), 3, "the group must be of rank 3");
+    let points = wythoff_orbit(group, rings);
+    let triangles = convex_hull(&points);
+    let vertices: Vec<vec3<f32>> = points
+        .iter()
+        .map(|&[x, y, z]| vec3(x as f32, y as f32, z as f32))
+        .collect();
+    Mesh3d::new(vertices, triangles)
+}
+
+/// Uniform polychoron given by the Wythoff construction with the circumradius of 2.
+/// `rings[i]` tells whether the node `i` of the diagram is ringed,
+/// e.g. `[false, true, false, false]` in [CoxeterGroup::B4] is the rectified tesseract.
+pub fn wythoff_polychoron(group: CoxeterGroup, rings: [bool; 4]) -> Mesh4d {
+    assert_eq!(group.rank(), 4, "the group must be of rank 4");
+    let points = wythoff_orbit(group, rings);
+    let tetrahedra = convex_hull(&points);
+    let vertices = points
+        .into_iter()
+        .map(|[x, y, z, w]| vec4(x as f32, y as f32, z as f32, w as f32))
+        .collect();
+    Mesh4d::new(vertices, tetrahedra)
+}
+
+/// Reflects the seed point in the mirrors until no new points appear.
+fn wythoff_orbit<const N: usize>(group: CoxeterGroup, rings: [bool; N]) -> Vec<[f64; N]> {
+    assert!(rings.contains(&true), "at least one node must be ringed");
+    let mirrors: [[f64; N]; N] = mirror_normals(&group.matrix());
+
+    // The seed is at the same distance from all ringed mirrors and lies on the rest,
+    // so that all edges have the same length.
+    // Mirror normals form a lower triangular matrix, solve by forward substitution.
+    let mut seed = [0.0; N];
+    for i in 0..N {
+        let target = if rings[i] { 1.0 } else { 0.0 };
+        let known: f64 = (0..i).map(|j| mirrors[i][j] * seed[j]).sum();
+        seed[i] = (target - known) / mirrors[i][i];
+    }
+
+    let key = |point: &[f64; N]| point.map(|x| (x * 1e4).round() as i64);
+    let mut seen: HashSet<[i64; N]> = HashSet::new();
+    seen.insert(key(&seed));
+    let mut points = vec![seed];
+    let mut queue = std::collections::VecDeque::from([seed]);
+    while let Some(point) = queue.pop_front() {
+        for normal in &mirrors {
+            let t: f64 = (0..N).map(|i| point[i] * normal[i]).sum::<f64>() * 2.0;
+            let image: [f64; N] = std::array::from_fn(|i| point[i] - normal[i] * t);
+            if seen.insert(key(&image)) {
+                points.push(image);
+                queue.push_back(image);
+            }
+        }
+    }
+
+    let radius = seed.iter().map(|x| x * x).sum::<f64>().sqrt();
+    points
+        .into_iter()
+        .map(|point| point.map(|x| x / radius * 2.0))
+        .collect()
+}
+
+/// Unit normals of the mirrors with the angles between them given by the Coxeter matrix.
+/// The normals are the rows of the Cholesky decomposition of the Gram matrix
+/// `G[i][j] = -cos(pi / m[i][j])`.
+fn mirror_normals<const N: usize>(matrix: &[Vec<usize>]) -> [[f64; N]; N] {
+    let gram = |i: usize, j: usize| -(std::f64::consts::PI / matrix[i][j] as f64).cos();
+    let mut normals = [[0.0; N]; N];
+    for i in 0..N {
+        for j in 0..=i {
+            let known: f64 = (0..j).map(|k| normals[i][k] * normals[j][k]).sum();
+            normals[i][j] = if i == j {
+                (gram(i, i) - known).max(0.0).sqrt()
+            } else {
+                (gram(i, j) - known) / normals[j][j]
+            };
+        }
+    }
+    normals
+}
+
+/// Polygonal faces of a convex polyhedron, found by merging its coplanar triangles,
+/// going counter-clockwise when viewed from the outside.
+#[cfg(test)]
+fn faces(mesh: &Mesh3d) -> Vec<Vec<usize>> {
+    let points: Vec<[f64; 3]> = mesh
+        .vertices()
+        .iter()
+        .map(|v| [v.x, v.y, v.z].map(f64::from))
+        .collect();
+    super::hull::hull_polygons(&points, mesh.triangles())
+}
+
+/// Polyhedral cells of a convex polychoron, found by merging its cohyperplanar tetrahedra.
+#[cfg(test)]
+fn cells(mesh: &Mesh4d) -> Vec<Vec<usize>> {
+    let points: Vec<[f64; 4]> = mesh
+        .vertices()
+        .iter()
+        .map(|v| [v.x, v.y, v.z, v.w].map(f64::from))
+        .collect();
+    super::hull::group_facets(&points, mesh.cells())
+        .into_iter()
+        .map(|(_, cell)| cell)
+        .collect()
+}
+
+#[test]
+fn test_wythoff_counts() {
+    let truncated_icosahedron = wythoff_polyhedron(CoxeterGroup::H3, [false, true, true]);
+    assert_eq!(truncated_icosahedron.vertices().len(), 60);
+    let faces = faces(&truncated_icosahedron);
+    assert_eq!(faces.len(), 32);
+    let mut sides: Vec<usize> = faces.iter().map(Vec::len).collect();
+    sides.sort();
+    assert_eq!(sides, [[5; 12].as_slice(), &[6; 20]].concat());
+
+    let rectified_tesseract = wythoff_polychoron(CoxeterGroup::B4, [false, true, false, false]);
+    assert_eq!(rectified_tesseract.vertices().len(), 32);
+    // 8 cuboctahedra and 16 tetrahedra
+    assert_eq!(cells(&rectified_tesseract).len(), 24);
+
+    let truncated_5cell = wythoff_polychoron(CoxeterGroup::A4, [true, true, false, false]);
+    assert_eq!(truncated_5cell.vertices().len(), 20);
+    // 5 truncated tetrahedra and 5 tetrahedra
+    let mut sizes: Vec<usize> = cells(&truncated_5cell).iter().map(Vec::len).collect();
+    sizes.sort();
+    assert_eq!(sizes, [[4; 5], [12; 5]].concat());
+}
+
+#[test]
+fn test_wythoff_face_order() {
+    // Consecutive vertices of a face of the cube are joined by its edges of length 2 / sqrt(3)
+    let cube = wythoff_polyhedron(CoxeterGroup::B3, [true, false, false]);
+    let faces = faces(&cube);
+    assert_eq!(faces.len(), 6);
+    let vertices = cube.vertices();
+    for face in &faces {
+        let [a, b, c, d] = face[..].try_into().unwrap();
+        for [p, q] in [[a, b], [b, c], [c, d], [d, a]] {
+            let len = (vertices[p] - vertices[q]).len();
+            assert!((len - 4.0 / 3.0_f32.sqrt()).abs() < 1e-4, "edge {len}");
+        }
+        let normal = vec3::cross(vertices[b] - vertices[a], vertices[c] - vertices[b]);
+        assert!(vec3::dot(normal, vertices[a]) > 0.0, "face looks inwards");
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_wythoff_degenerate_duoprism() {
+    wythoff_polychoron(CoxeterGroup::Duoprism(1, 4), [true, false, true, false]);
+}
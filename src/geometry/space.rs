@@ -1,91 +1,403 @@
 use geng::prelude::*;
 
 use super::{
+    hyperplane::Hyperplane,
     mat5,
-    shape::{Tetrahedron4d, Triangle, Triangle4d},
-    vec5,
+    sdf::{marching_tetrahedra, Sdf4d},
+    shape::{Mesh3d, Mesh4d},
+    vec4_len, vec4_norm, vec5,
+    vec_n::VecN,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Space {
+    pub normal: vec4<f32>,
     pub offset: f32,
 }
 
 impl Space {
+    pub fn hyperplane(&self) -> Hyperplane<4> {
+        Hyperplane::new(self.normal.into(), self.offset)
+    }
+
+    /// Returns an orthonormal basis of the space: three unit vectors orthogonal
+    /// to the normal and to each other.
+    /// Together with the normal they form a frame with the same handedness as xyzw,
+    /// so a space with the normal along w keeps the x, y, and z axes.
+    pub fn basis(&self) -> [vec4<f32>; 3] {
+        let basis = self.hyperplane().basis();
+        [basis[0].into(), basis[1].into(), basis[2].into()]
+    }
+
+    /// Transformation into the local coordinates of the space:
+    /// xyz are the coordinates along the basis, w is the distance to the space.
     pub fn matrix(&self) -> mat5<f32> {
-        mat5::translate(vec4::UNIT_W * self.offset)
+        let normal = vec4_norm(self.normal);
+        let [_, y, z] = self.basis();
+        let origin = normal * self.offset;
+        mat5::look_at(origin, origin + normal, y, z)
     }
 
+    #[cfg(test)]
     pub fn project(&self, point: vec4<f32>) -> vec4<f32> {
-        point - vec4::UNIT_W * self.distance(point)
+        self.hyperplane().project(point.into()).into()
     }
 
     pub fn project3d(&self, point: vec4<f32>) -> vec3<f32> {
-        let point = self.project(point);
-        let point = (self.matrix() * vec5(point.x, point.y, point.z, point.w, 1.0)).into_4d();
-        vec3(point.x, point.y, point.z)
+        self.hyperplane().local_projection()(point.into()).into()
+    }
+
+    /// Inverse of [Self::project3d]: returns the 4d point with the given local coordinates
+    /// at the given `distance` from the space.
+    #[cfg(test)]
+    pub fn unproject(&self, point: vec3<f32>, distance: f32) -> vec4<f32> {
+        self.hyperplane()
+            .unproject_local(point.into(), distance)
+            .into()
     }
 
     pub fn distance(&self, point: vec4<f32>) -> f32 {
-        vec4::dot(vec4::UNIT_W, point) - self.offset
+        self.hyperplane().distance(point.into())
     }
 
-    pub fn intersect_segment(&self, p1: vec4<f32>, p2: vec4<f32>) -> Option<vec4<f32>> {
-        let d1 = self.distance(p1);
-        let d2 = self.distance(p2);
+    /// Whether the hypersphere transformed by the `matrix` reaches the space.
+    /// The `matrix` is expected to only rotate, scale and translate,
+    /// the radius is scaled by the largest of the scales.
+    pub fn intersects_sphere(&self, center: vec4<f32>, radius: f32, matrix: mat5<f32>) -> bool {
+        let origin = (matrix * vec5(center.x, center.y, center.z, center.w, 1.0)).into_4d();
+        let scale = (0..4)
+            .map(|i| vec4_len(matrix.col(i).xyzw()))
+            .fold(0.0, f32::max);
+        self.distance(origin).abs() <= radius * scale
+    }
 
-        if (d1 - d2).abs() < 1e-5 {
-            // Parallel
-            return None;
+    /// Calculate a cross section of the `mesh` transformed by the `matrix` with the space.
+    ///
+    /// The mesh is sliced by [Hyperplane::slice_simplices], so the triangles of the section
+    /// share their vertices and form a watertight mesh in the local coordinates of the space.
+    /// The triangles look the same way as the outward normals of the cells they come from,
+    /// which requires the `matrix` to only rotate, reflect, scale uniformly and translate.
+    ///
+    /// Vertices lying in the space are classified by [is_below](super::hyperplane::is_below),
+    /// so the space touching a vertex or containing an edge or a face gives a consistent section.
+    pub fn cross_sect(&self, mesh: &Mesh4d, matrix: mat5<f32>) -> Mesh3d {
+        // Skip the whole mesh if its bounding sphere does not reach the space
+        let (center, radius) = mesh.bounding_sphere();
+        if !self.intersects_sphere(center, radius, matrix) {
+            return Mesh3d::new(Vec::new(), Vec::new());
         }
 
-        let t = d1 / (d1 - d2);
-        (0.0..=1.0).contains(&t).then_some(p1 + t * (p2 - p1))
-    }
+        let transformed: Vec<VecN<4>> = mesh
+            .vertices()
+            .iter()
+            .map(|v| (matrix * vec5(v.x, v.y, v.z, v.w, 1.0)).into_4d().into())
+            .collect();
+        let hyperplane = self.hyperplane();
+        let cells = mesh.cells().iter().map(|cell| cell.as_slice());
+        let (section, sources) = hyperplane.slice_simplices(&transformed, cells);
 
-    pub fn intersect_tetrahedron(&self, tetrahedron: &Tetrahedron4d) -> Vec<Triangle4d> {
-        let points: Vec<vec4<f32>> = tetrahedron
-            .edges()
-            .into_iter()
-            .flat_map(|(p1, p2)| self.intersect_segment(p1, p2))
+        // Project into the local coordinates, with the basis computed once
+        let project = hyperplane.local_projection::<3>();
+        let vertices: Vec<vec3<f32>> = section
+            .vertices
+            .iter()
+            .map(|&v| project(v).into())
             .collect();
-        match points[..] {
-            [a, b, c] => vec![Triangle4d {
-                vertices: [a, b, c],
-            }],
-            [a, b, c, d] => vec![
-                Triangle4d {
-                    vertices: [a, b, c],
-                },
-                Triangle4d {
-                    vertices: [d, c, b],
-                },
-            ],
-            _ => vec![],
+        // Directions do not depend on the offset of the space
+        let origin = project(VecN::ZERO);
+        let project_direction = |v: VecN<4>| vec3::from(project(v) - origin);
+
+        let triangles = section
+            .simplices
+            .iter()
+            .zip(sources)
+            .map(|(simplex, cell)| {
+                let mut triangle = [simplex[0], simplex[1], simplex[2]];
+
+                // The section of the cell looks the same way as the cell itself.
+                // The matrix keeps the angles, so it maps the outward normals to the outward ones.
+                let normal = mesh.cell_normal(cell);
+                let normal = matrix * vec5(normal.x, normal.y, normal.z, normal.w, 0.0);
+                let normal = project_direction(normal.xyzw().into());
+                let [a, b, c] = triangle.map(|i| vertices[i]);
+                if vec3::dot(vec3::cross(b - a, c - a), normal) < 0.0 {
+                    triangle.swap(0, 1);
+                }
+                triangle
+            })
+            .collect();
+
+        Mesh3d::new(vertices, triangles)
+    }
+
+    /// Calculate a cross section of an object given by a signed distance function
+    /// and transformed by the `matrix` with the space, using marching tetrahedra.
+    /// The `matrix` must be a similarity transformation,
+    /// i.e. consist of a rotation, a translation and a uniform scaling.
+    ///
+    /// The grid of `resolution` cells along each axis covers the part of the space
+    /// inside the bounding sphere of the object.
+    pub fn cross_sect_sdf(&self, sdf: &dyn Sdf4d, matrix: mat5<f32>, resolution: usize) -> Mesh3d {
+        let center = matrix.col(4).xyzw();
+        let scale = vec4_len(matrix.col(0).xyzw());
+        let radius = sdf.bounding_radius() * scale;
+        let distance = self.distance(center);
+        if distance.abs() >= radius || scale == 0.0 {
+            return Mesh3d::new(Vec::new(), Vec::new());
         }
+        // Radius of the sphere where the space cuts the bounding sphere,
+        // with a margin so that the surface never touches the border of the grid
+        let half_size = (radius * radius - distance * distance).sqrt() * 1.1;
+
+        let to_object = matrix.inverse() * self.matrix().inverse();
+        marching_tetrahedra(self.project3d(center), half_size, resolution, |point| {
+            let point = to_object * vec5(point.x, point.y, point.z, 0.0, 1.0);
+            sdf.distance(point.into_4d())
+        })
+    }
+}
+
+#[test]
+fn test_space_project() {
+    macro_rules! check {
+        ($a:expr, $b:expr) => {{
+            let a = $a;
+            let b = $b;
+            let delta = (b - a).len();
+            assert!(
+                delta < 1e-5,
+                "\n  left: `{:?}`,\n right: `{:?}`,\n delta: `{}`",
+                a,
+                b,
+                delta
+            );
+        }};
     }
 
-    /// Calculate a cross section of `geometry` with the space.
-    pub fn cross_sect(
-        &self,
-        geometry: impl IntoIterator<Item = impl std::borrow::Borrow<Tetrahedron4d>>,
-    ) -> Vec<Triangle> {
-        let mut triangles: Vec<Triangle> = geometry
-            .into_iter()
-            .flat_map(|tetrahedron| {
-                self.intersect_tetrahedron(tetrahedron.borrow())
-                    .into_iter()
-                    .map(|triangle| Triangle::new(triangle.vertices.map(|v| self.project3d(v))))
+    let offsets = [0.0, 2.0, -3.0];
+
+    for offset in offsets {
+        println!("Testing offset {}", offset);
+
+        let space = Space {
+            normal: vec4::UNIT_W,
+            offset,
+        };
+        check!(
+            space.project3d(vec4(1.0, 2.0, 3.0, 10.0)),
+            vec3(1.0, 2.0, 3.0)
+        );
+        check!(
+            space.unproject(vec3(1.0, 2.0, 3.0), 1.0),
+            vec4(1.0, 2.0, 3.0, offset + 1.0)
+        );
+
+        let space = Space {
+            normal: vec4::UNIT_X,
+            offset,
+        };
+        check!(
+            space.project(vec4(10.0, 2.0, 3.0, 4.0)),
+            vec4(offset, 2.0, 3.0, 4.0)
+        );
+        check!(
+            space.project3d(vec4(10.0, 2.0, 3.0, 4.0)),
+            vec3(2.0, 3.0, -4.0)
+        );
+
+        let space = Space {
+            normal: vec4(1.0, 1.0, 1.0, 1.0),
+            offset,
+        };
+        let basis = space.basis();
+        for (i, a) in basis.into_iter().enumerate() {
+            assert!(vec4::dot(a, space.normal).abs() < 1e-5);
+            for (j, b) in basis.into_iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((vec4::dot(a, b) - expected).abs() < 1e-5);
+            }
+        }
+        let point = vec3(1.0, -2.0, 0.5);
+        check!(space.project3d(space.unproject(point, 3.0)), point);
+        let local = space.matrix() * vec5(1.0, -2.0, 0.5, 3.0, 1.0);
+        check!(local.xyz(), space.project3d(vec4(1.0, -2.0, 0.5, 3.0)));
+        assert!((local.w - space.distance(vec4(1.0, -2.0, 0.5, 3.0))).abs() < 1e-5);
+        assert!((space.distance(space.unproject(point, 3.0)) - 3.0).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_cross_sect_degenerate() {
+    // The space touches the vertices of the tesseract, or contains its faces or cells
+    let tesseract = super::shape::unit_tesseract();
+    let cases = [
+        (vec4::UNIT_W, 1.0, 8.0),
+        (vec4::UNIT_W, -1.0, 0.0),
+        (vec4(1.0, 1.0, 0.0, 0.0), 0.0, 8.0 * 2.0.sqrt()),
+        (vec4(1.0, 1.0, 1.0, 1.0), 1.0, 8.0 / 3.0),
+    ];
+    for (normal, offset, expected) in cases {
+        let space = Space { normal, offset };
+        let section = space.cross_sect(&tesseract, mat5::identity());
+
+        // Closed: every edge is shared by two triangles
+        let mut edges: HashMap<[usize; 2], usize> = HashMap::new();
+        for triangle in section.triangles() {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                *edges.entry([a.min(b), a.max(b)]).or_default() += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2), "{normal:?}");
+
+        let volume: f32 = section
+            .triangles()
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|i| section.vertices()[i]);
+                vec3::dot(a, vec3::cross(b, c)) / 6.0
             })
-            .collect();
-        let center = triangles
+            .sum();
+        assert!(
+            (volume.abs() - expected).abs() < 1e-4,
+            "{normal:?}: volume {volume} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn test_cross_sect_orientation() {
+    // Two separate tesseracts, also mirrored, and a torus-shaped section of a spheritorus:
+    // a single center is inside neither of them
+    let tesseract = super::shape::unit_tesseract();
+    let shifted = |shift: f32| {
+        let tesseract = &tesseract;
+        tesseract
+            .cells()
+            .iter()
+            .map(move |cell| super::shape::Tetrahedron4d {
+                vertices: cell.map(|i| tesseract.vertices()[i] + vec4(shift, 0.0, 0.0, 0.0)),
+            })
+    };
+    let pair = Mesh4d::from_tetrahedra(shifted(-2.0).chain(shifted(2.0)));
+    let mirror = mat5::scale(vec4(-1.0, 1.0, 1.0, 1.0));
+    let cases = [
+        (pair.clone(), mat5::identity(), 16.0),
+        (pair, mirror, 16.0),
+        (super::shape::unit_spheritorus(16), mat5::identity(), 0.0),
+    ];
+
+    let space = Space {
+        normal: vec4::UNIT_W,
+        offset: 0.1,
+    };
+    for (mesh, matrix, expected) in cases {
+        let section = space.cross_sect(&mesh, matrix);
+
+        // Neighbouring triangles go around their shared edge in opposite directions
+        let mut directed: HashSet<[usize; 2]> = HashSet::new();
+        for &[a, b, c] in section.triangles() {
+            for edge in [[a, b], [b, c], [c, a]] {
+                assert!(directed.insert(edge), "edge {edge:?} is used twice");
+            }
+        }
+        for &[a, b] in &directed {
+            assert!(directed.contains(&[b, a]), "edge {:?} is open", [a, b]);
+        }
+
+        // Outward normals give a positive volume
+        let volume: f32 = section
+            .triangles()
             .iter()
-            .flat_map(|t| t.vertices)
-            .fold(vec3::ZERO, vec3::add)
-            / (triangles.len() as f32 * 3.0);
-        for triangle in &mut triangles {
-            triangle.look_away_from(center);
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|i| section.vertices()[i]);
+                vec3::dot(a, vec3::cross(b, c)) / 6.0
+            })
+            .sum();
+        assert!(volume > 0.0, "volume {volume}");
+        if expected > 0.0 {
+            assert!(
+                (volume - expected).abs() < 1e-3,
+                "volume {volume} != {expected}"
+            );
         }
-        triangles
+    }
+}
+
+#[test]
+fn test_measure() {
+    // A cube of side 2 centered at (1, 0, -0.5)
+    let tesseract = super::shape::unit_tesseract();
+    let space = Space {
+        normal: vec4::UNIT_W,
+        offset: 0.3,
+    };
+    let matrix = mat5::translate(vec4(1.0, 0.0, -0.5, 0.0));
+    let measure = space.cross_sect(&tesseract, matrix).measure();
+    assert!((measure.volume - 8.0).abs() < 1e-3, "{measure:?}");
+    assert!((measure.surface_area - 24.0).abs() < 1e-3, "{measure:?}");
+    assert!(
+        (measure.centroid - vec3(1.0, 0.0, -0.5)).len() < 1e-4,
+        "{measure:?}"
+    );
+}
+
+#[test]
+fn test_intersects_sphere() {
+    let space = Space {
+        normal: vec4::UNIT_W,
+        offset: 0.0,
+    };
+    // The hypersphere of radius 2 is moved from the x axis onto the w axis at `w + 2`
+    let matrix = |w: f32| {
+        mat5::translate(vec4(0.0, 0.0, 0.0, w))
+            * mat5::rotate_plane(vec4::UNIT_X, vec4::UNIT_W, Angle::from_degrees(90.0))
+            * mat5::scale_uniform(2.0)
+    };
+    let center = vec4(1.0, 0.0, 0.0, 0.0);
+    for (w, expected) in [
+        (0.1, false),
+        (-0.1, true),
+        (-3.9, true),
+        (-4.1, false),
+        (-1e3, false),
+    ] {
+        assert_eq!(
+            space.intersects_sphere(center, 1.0, matrix(w)),
+            expected,
+            "w {w}"
+        );
+    }
+
+    // The largest scale is used for the radius
+    let matrix =
+        |w: f32| mat5::translate(vec4(0.0, 0.0, 0.0, w)) * mat5::scale(vec4(0.5, 3.0, 1.0, 1.0));
+    assert!(space.intersects_sphere(vec4::ZERO, 1.0, matrix(2.9)));
+    assert!(!space.intersects_sphere(vec4::ZERO, 1.0, matrix(3.1)));
+    assert!(!space.intersects_sphere(vec4::ZERO, 1.0, matrix(-3.1)));
+}
+
+#[test]
+fn test_cross_sect_bounds() {
+    // A tesseract of side 2 shrunk to the side 1, rotated in the xy and zw planes
+    let tesseract = super::shape::unit_tesseract();
+    let space = Space {
+        normal: vec4::UNIT_W,
+        offset: 0.0,
+    };
+    let matrix = |w: f32| {
+        mat5::translate(vec4(1.0, 0.0, 0.0, w))
+            * mat5::rotate_plane(vec4::UNIT_X, vec4::UNIT_Y, Angle::from_degrees(30.0))
+            * mat5::rotate_plane(vec4::UNIT_Z, vec4::UNIT_W, Angle::from_degrees(90.0))
+            * mat5::scale_uniform(0.5)
+    };
+    for w in [0.6, -0.6, 1.1, 1e3] {
+        let section = space.cross_sect(&tesseract, matrix(w));
+        assert!(section.triangles().is_empty(), "w {w}");
+    }
+    for w in [0.45, -0.45, 0.0] {
+        let section = space.cross_sect(&tesseract, matrix(w));
+        let volume = section.measure().volume;
+        assert!((volume - 1.0).abs() < 1e-4, "w {w}: volume {volume}");
     }
 }
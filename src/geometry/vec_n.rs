@@ -0,0 +1,147 @@
+use geng::prelude::*;
+
+use super::vec_5::vec5;
+
+/// Vector of an arbitrary dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VecN<const N: usize>(pub [f32; N]);
+
+impl<const N: usize> VecN<N> {
+    pub const ZERO: Self = Self([0.0; N]);
+
+    /// Unit vector along the axis `i`.
+    pub fn unit(i: usize) -> Self {
+        let mut result = Self::ZERO;
+        result[i] = 1.0;
+        result
+    }
+
+    pub fn dot(a: Self, b: Self) -> f32 {
+        a.0.iter().zip(b.0).map(|(a, b)| a * b).sum()
+    }
+
+    pub fn len(self) -> f32 {
+        Self::dot(self, self).sqrt()
+    }
+
+    pub fn normalize_or_zero(self) -> Self {
+        let len = self.len();
+        if len.approx_eq(&0.0) {
+            return Self::ZERO;
+        }
+        self / len
+    }
+
+    /// Map every component
+    pub fn map(self, f: impl Fn(f32) -> f32) -> Self {
+        Self(self.0.map(f))
+    }
+}
+
+impl<const N: usize> Index<usize> for VecN<N> {
+    type Output = f32;
+    fn index(&self, i: usize) -> &f32 {
+        &self.0[i]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for VecN<N> {
+    fn index_mut(&mut self, i: usize) -> &mut f32 {
+        &mut self.0[i]
+    }
+}
+
+impl<const N: usize> Add for VecN<N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self[i] + rhs[i]))
+    }
+}
+
+impl<const N: usize> AddAssign for VecN<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: usize> Sub for VecN<N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self[i] - rhs[i]))
+    }
+}
+
+impl<const N: usize> SubAssign for VecN<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize> Neg for VecN<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+impl<const N: usize> Mul<f32> for VecN<N> {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self {
+        self.map(|x| x * rhs)
+    }
+}
+
+impl<const N: usize> Div<f32> for VecN<N> {
+    type Output = Self;
+    fn div(self, rhs: f32) -> Self {
+        self.map(|x| x / rhs)
+    }
+}
+
+impl From<vec2<f32>> for VecN<2> {
+    fn from(v: vec2<f32>) -> Self {
+        Self([v.x, v.y])
+    }
+}
+
+impl From<VecN<2>> for vec2<f32> {
+    fn from(VecN([x, y]): VecN<2>) -> Self {
+        vec2(x, y)
+    }
+}
+
+impl From<vec3<f32>> for VecN<3> {
+    fn from(v: vec3<f32>) -> Self {
+        Self([v.x, v.y, v.z])
+    }
+}
+
+impl From<VecN<3>> for vec3<f32> {
+    fn from(VecN([x, y, z]): VecN<3>) -> Self {
+        vec3(x, y, z)
+    }
+}
+
+impl From<vec4<f32>> for VecN<4> {
+    fn from(v: vec4<f32>) -> Self {
+        Self([v.x, v.y, v.z, v.w])
+    }
+}
+
+impl From<VecN<4>> for vec4<f32> {
+    fn from(VecN([x, y, z, w]): VecN<4>) -> Self {
+        vec4(x, y, z, w)
+    }
+}
+
+impl From<vec5<f32>> for VecN<5> {
+    fn from(v: vec5<f32>) -> Self {
+        Self([v.x, v.y, v.z, v.w, v.v])
+    }
+}
+
+impl From<VecN<5>> for vec5<f32> {
+    fn from(VecN([x, y, z, w, v]): VecN<5>) -> Self {
+        vec5(x, y, z, w, v)
+    }
+}
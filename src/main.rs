@@ -1,10 +1,16 @@
 mod camera3d;
 mod geometry;
+mod mode;
+mod state1d;
 mod state2d;
 mod state3d;
+mod state4d;
 
+use self::mode::DimensionMode;
+use self::state1d::State1d;
 use self::state2d::State2d;
 use self::state3d::State3d;
+use self::state4d::State4d;
 
 use geng::prelude::*;
 use geng_utils::{bounded::Bounded, conversions::Vec2RealConversions, key as key_utils};
@@ -35,13 +41,21 @@ pub struct Config {
     scale_max: f32,
     speed: Bounded<f32>,
     rotation_speed_degrees: Bounded<f32>,
+    offset_5d: Bounded<f32>,
+    offset_4d: Bounded<f32>,
+    shape_resolution: usize,
+    sdf_resolution: usize,
+    /// Show the area and perimeter, or the volume and surface area of every section.
+    show_measurements: bool,
     background_color: Rgba<f32>,
     object_colors: Vec<Rgba<f32>>,
 }
 
 enum Mode {
+    Mode1d,
     Mode2d,
     Mode3d,
+    Mode4d,
 }
 
 pub struct State {
@@ -52,22 +66,31 @@ pub struct State {
     touch_pos: vec2<f32>,
     paused: bool,
     mode: Mode,
+    include_2d_in_1d: bool,
     include_3d_in_2d: bool,
+    state1d: State1d,
     state2d: State2d,
     state3d: State3d,
+    state4d: State4d,
     drag: Option<Drag>,
+    button1d: Aabb2<f32>,
     button2d: Aabb2<f32>,
     button3d: Aabb2<f32>,
-    button_include3d: Aabb2<f32>,
+    button4d: Aabb2<f32>,
+    button_include: Aabb2<f32>,
     slider_object_limit: Aabb2<f32>,
     slider_speed: Aabb2<f32>,
     slider_rotation_speed: Aabb2<f32>,
+    slider_offset_5d: Aabb2<f32>,
+    slider_offset_4d: Aabb2<f32>,
 }
 
 enum Drag {
     SliderObjects,
     SliderSpeed,
     SliderRotation,
+    SliderOffset5d,
+    SliderOffset4d,
 }
 
 impl State {
@@ -75,18 +98,25 @@ impl State {
         Self {
             paused: false,
             mode: Mode::Mode2d,
+            include_2d_in_1d: true,
             include_3d_in_2d: false,
             cursor_pos: vec2::ZERO,
             touch_pos: vec2::ZERO,
-            state2d: State2d::new(geng.clone(), assets.clone()),
-            state3d: State3d::new(geng.clone(), assets.clone()),
+            state1d: State1d::new(geng.clone(), &config),
+            state2d: State2d::new(geng.clone(), assets.clone(), &config),
+            state3d: State3d::new(geng.clone(), assets.clone(), &config),
+            state4d: State4d::new(geng.clone(), assets.clone()),
             drag: None,
+            button1d: Aabb2::ZERO,
             button2d: Aabb2::ZERO,
             button3d: Aabb2::ZERO,
-            button_include3d: Aabb2::ZERO,
+            button4d: Aabb2::ZERO,
+            button_include: Aabb2::ZERO,
             slider_object_limit: Aabb2::ZERO,
             slider_speed: Aabb2::ZERO,
             slider_rotation_speed: Aabb2::ZERO,
+            slider_offset_5d: Aabb2::ZERO,
+            slider_offset_4d: Aabb2::ZERO,
             geng,
             assets,
             config,
@@ -101,6 +131,10 @@ impl State {
             self.drag = Some(Drag::SliderSpeed);
         } else if self.slider_rotation_speed.contains(pos) {
             self.drag = Some(Drag::SliderRotation);
+        } else if self.slider_offset_5d.contains(pos) {
+            self.drag = Some(Drag::SliderOffset5d);
+        } else if self.slider_offset_4d.contains(pos) {
+            self.drag = Some(Drag::SliderOffset4d);
         }
     }
 
@@ -121,6 +155,14 @@ impl State {
                         / self.slider_rotation_speed.width();
                     self.config.rotation_speed_degrees.set_ratio(t);
                 }
+                Drag::SliderOffset5d => {
+                    let t = (pos.x - self.slider_offset_5d.min.x) / self.slider_offset_5d.width();
+                    self.config.offset_5d.set_ratio(t);
+                }
+                Drag::SliderOffset4d => {
+                    let t = (pos.x - self.slider_offset_4d.min.x) / self.slider_offset_4d.width();
+                    self.config.offset_4d.set_ratio(t);
+                }
             }
         }
     }
@@ -128,12 +170,20 @@ impl State {
     fn touch_release(&mut self, pos: vec2<f32>) {
         if (self.touch_pos - pos).len_sqr() < 1.0 {
             // Click
-            if self.button2d.contains(pos) {
+            if self.button1d.contains(pos) {
+                self.mode = Mode::Mode1d;
+            } else if self.button2d.contains(pos) {
                 self.mode = Mode::Mode2d;
             } else if self.button3d.contains(pos) {
                 self.mode = Mode::Mode3d;
-            } else if matches!(self.mode, Mode::Mode2d) && self.button_include3d.contains(pos) {
-                self.include_3d_in_2d = !self.include_3d_in_2d;
+            } else if self.button4d.contains(pos) {
+                self.mode = Mode::Mode4d;
+            } else if self.button_include.contains(pos) {
+                match self.mode {
+                    Mode::Mode1d => self.include_2d_in_1d = !self.include_2d_in_1d,
+                    Mode::Mode2d => self.include_3d_in_2d = !self.include_3d_in_2d,
+                    Mode::Mode3d | Mode::Mode4d => {}
+                }
             }
         }
         self.drag = None;
@@ -198,25 +248,37 @@ impl State {
             .translate(vec2(0.0, -button_size.y));
         let pos = vec2(0.0, framebuffer_size.y) + vec2(1.0, -1.0) * font_size;
 
-        self.button2d = button.translate(pos);
+        self.button1d = button.translate(pos);
+        draw_button("2D -> 1D", self.button1d, matches!(self.mode, Mode::Mode1d));
+
+        self.button2d = button.translate(pos - vec2(0.0, button_size.y + font_size));
         draw_button("3D -> 2D", self.button2d, matches!(self.mode, Mode::Mode2d));
 
-        self.button3d = button.translate(pos - vec2(0.0, button_size.y + font_size));
+        self.button3d = button.translate(pos - vec2(0.0, button_size.y + font_size) * 2.0);
         draw_button("4D -> 3D", self.button3d, matches!(self.mode, Mode::Mode3d));
 
-        // Tickbox
-        if let Mode::Mode2d = self.mode {
+        self.button4d = button.translate(pos - vec2(0.0, button_size.y + font_size) * 3.0);
+        draw_button("5D -> 3D", self.button4d, matches!(self.mode, Mode::Mode4d));
+
+        // Tickbox to include the higher dimension
+        let tickbox_state = match self.mode {
+            Mode::Mode1d => Some((self.button1d, self.include_2d_in_1d, "2d")),
+            Mode::Mode2d => Some((self.button2d, self.include_3d_in_2d, "3d")),
+            Mode::Mode3d | Mode::Mode4d => None,
+        };
+        self.button_include = Aabb2::ZERO;
+        if let Some((button, include, text)) = tickbox_state {
             let tickbox_size = vec2::splat(1.5) * font_size;
             let tickbox = Aabb2::ZERO.extend_symmetric(tickbox_size / 2.0);
-            let pos = geng_utils::layout::aabb_pos(self.button2d, vec2(1.0, 0.5));
+            let pos = geng_utils::layout::aabb_pos(button, vec2(1.0, 0.5));
 
-            self.button_include3d = tickbox
+            self.button_include = tickbox
                 .translate(pos)
                 .translate(vec2(font_size + tickbox.width() / 2.0, 0.0));
-            let position = self.button_include3d;
+            let position = self.button_include;
 
             // Outline
-            let color = if self.include_3d_in_2d {
+            let color = if include {
                 Rgba::try_from("#aaa").unwrap()
             } else {
                 Rgba::try_from("#555").unwrap()
@@ -243,7 +305,7 @@ impl State {
             self.geng.default_font().draw(
                 framebuffer,
                 camera,
-                "3d",
+                text,
                 vec2::splat(geng::TextAlign::CENTER),
                 mat3::translate(position.center() + vec2(0.0, -font_size / 4.0))
                     * mat3::scale_uniform(font_size),
@@ -327,6 +389,25 @@ impl State {
             self.slider_rotation_speed,
             self.config.rotation_speed_degrees.get_ratio(),
         );
+
+        if let Mode::Mode4d = self.mode {
+            self.slider_offset_5d = slider.translate(pos + vec2(0.0, -font_size * 6.0));
+            draw_slider(
+                &format!("5D offset {:.2}", self.config.offset_5d.value()),
+                self.slider_offset_5d,
+                self.config.offset_5d.get_ratio(),
+            );
+
+            self.slider_offset_4d = slider.translate(pos + vec2(0.0, -font_size * 8.0));
+            draw_slider(
+                &format!("4D offset {:.2}", self.config.offset_4d.value()),
+                self.slider_offset_4d,
+                self.config.offset_4d.get_ratio(),
+            );
+        } else {
+            self.slider_offset_5d = Aabb2::ZERO;
+            self.slider_offset_4d = Aabb2::ZERO;
+        }
     }
 }
 
@@ -334,12 +415,18 @@ impl geng::State for State {
     fn update(&mut self, delta_time: f64) {
         if !self.paused {
             match self.mode {
+                Mode::Mode1d => {
+                    self.state1d.update(&self.config, delta_time);
+                }
                 Mode::Mode2d => {
                     self.state2d.update(&self.config, delta_time);
                 }
                 Mode::Mode3d => {
                     self.state3d.update(&self.config, delta_time);
                 }
+                Mode::Mode4d => {
+                    self.state4d.update(&self.config, delta_time);
+                }
             }
         }
     }
@@ -358,6 +445,9 @@ impl geng::State for State {
         if key_utils::is_event_press(&event, [geng::Key::P]) {
             self.paused = !self.paused;
         }
+        if key_utils::is_event_press(&event, [geng::Key::M]) {
+            self.config.show_measurements = !self.config.show_measurements;
+        }
 
         // TODO: multitouch
         if let geng::Event::TouchStart(touch) = &event {
@@ -376,8 +466,10 @@ impl geng::State for State {
 
         if pass_to_state {
             match self.mode {
+                Mode::Mode1d => self.state1d.handle_event(&event),
                 Mode::Mode2d => self.state2d.handle_event(&event),
                 Mode::Mode3d => self.state3d.handle_event(&event),
+                Mode::Mode4d => self.state4d.handle_event(&event),
             }
         }
     }
@@ -392,6 +484,10 @@ impl geng::State for State {
 
         // State
         match self.mode {
+            Mode::Mode1d => {
+                self.state1d
+                    .draw(&self.config, self.include_2d_in_1d, framebuffer);
+            }
             Mode::Mode2d => {
                 self.state2d
                     .draw(&self.config, self.include_3d_in_2d, framebuffer);
@@ -399,6 +495,9 @@ impl geng::State for State {
             Mode::Mode3d => {
                 self.state3d.draw(&self.config, framebuffer);
             }
+            Mode::Mode4d => {
+                self.state4d.draw(&self.config, framebuffer);
+            }
         }
 
         self.draw_ui(framebuffer);
@@ -0,0 +1,615 @@
+use crate::Config;
+
+use std::collections::VecDeque;
+
+use geng::prelude::*;
+use geng_utils::{conversions::Vec2RealConversions, key as key_utils};
+
+/// A shape that can be toggled on and off for spawning.
+pub struct Prefab<T> {
+    pub name: String,
+    active: bool,
+    pub ui_checkbox: Aabb2<f32>,
+    shape: Option<T>,
+    /// Builds the shape when the prefab is activated for the first time.
+    build: Option<Box<dyn FnOnce() -> T>>,
+}
+
+impl<T> Prefab<T> {
+    pub fn new(name: &str, active: bool, shape: T) -> Self {
+        Self {
+            name: name.to_string(),
+            active,
+            ui_checkbox: Aabb2::ZERO,
+            shape: Some(shape),
+            build: None,
+        }
+    }
+
+    /// A prefab with an expensive shape, which is only built once the prefab is activated.
+    pub fn lazy(name: &str, active: bool, build: impl FnOnce() -> T + 'static) -> Self {
+        let mut prefab = Self {
+            name: name.to_string(),
+            active: false,
+            ui_checkbox: Aabb2::ZERO,
+            shape: None,
+            build: Some(Box::new(build)),
+        };
+        prefab.set_active(active);
+        prefab
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        if active {
+            if let Some(build) = self.build.take() {
+                self.shape = Some(build());
+            }
+        }
+    }
+
+    /// The shape, if it has been built.
+    pub fn shape(&self) -> Option<&T> {
+        self.shape.as_ref()
+    }
+}
+
+/// The cursor, moved by the mouse or a single touch.
+#[derive(Default)]
+pub struct Pointer {
+    pub cursor_pos: vec2<f32>,
+    /// Where the last press started.
+    touch_pos: vec2<f32>,
+}
+
+/// What an event did to the [Pointer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerAction {
+    Press,
+    Move,
+    Release,
+    /// A release close to where the press started.
+    Click,
+}
+
+impl Pointer {
+    /// Tracks the cursor and returns the action, if the event affected the pointer.
+    pub fn handle_event(&mut self, event: &geng::Event) -> Option<PointerAction> {
+        match event {
+            geng::Event::TouchMove(touch) => {
+                self.cursor_pos = touch.position.as_f32();
+                Some(PointerAction::Move)
+            }
+            geng::Event::CursorMove { position } => {
+                self.cursor_pos = position.as_f32();
+                Some(PointerAction::Move)
+            }
+            // TODO: multitouch
+            geng::Event::TouchStart(touch) => Some(self.press(touch.position.as_f32())),
+            geng::Event::TouchEnd(_) => Some(self.release()),
+            _ if key_utils::is_event_press(event, [geng::MouseButton::Left]) => {
+                Some(self.press(self.cursor_pos))
+            }
+            _ if key_utils::is_event_release(event, [geng::MouseButton::Left]) => {
+                Some(self.release())
+            }
+            _ => None,
+        }
+    }
+
+    fn press(&mut self, pos: vec2<f32>) -> PointerAction {
+        self.touch_pos = pos;
+        self.cursor_pos = pos;
+        PointerAction::Press
+    }
+
+    fn release(&self) -> PointerAction {
+        if (self.touch_pos - self.cursor_pos).len_sqr() < 1.0 {
+            PointerAction::Click
+        } else {
+            PointerAction::Release
+        }
+    }
+}
+
+/// A mode where objects drift through a section one dimension lower.
+///
+/// The implementor provides the dimension-specific parts: where the objects appear,
+/// how they move and how far they are from the section.
+/// Spawning, drifting, despawning, pointer input and toggling the prefabs are shared.
+pub trait DimensionMode {
+    type Shape: Clone;
+    type Object;
+
+    /// Number of objects approaching the section at the 100% density.
+    const MAX_APPROACHING: f32;
+    /// Minimum distance between the spawned objects relative to the sum of their scales.
+    const SPACING: f32;
+
+    fn prefabs(&self) -> &[Prefab<Self::Shape>];
+    fn prefabs_mut(&mut self) -> &mut [Prefab<Self::Shape>];
+    fn objects(&self) -> &[Self::Object];
+    fn objects_mut(&mut self) -> &mut Vec<Self::Object>;
+    fn pointer(&self) -> &Pointer;
+    fn pointer_mut(&mut self) -> &mut Pointer;
+
+    fn scale(object: &Self::Object) -> f32;
+
+    /// Distance between the centers of the objects.
+    fn distance(a: &Self::Object, b: &Self::Object) -> f32;
+
+    /// Signed distance from the object to the section, negative before crossing it.
+    fn depth(&self, object: &Self::Object) -> f32;
+
+    /// Objects further past the section are deleted.
+    fn despawn_depth(&self, _object: &Self::Object) -> f32 {
+        5.0
+    }
+
+    /// Creates an object with a random orientation at a random visible position
+    /// at the given `depth` from the section.
+    fn spawn(
+        &self,
+        config: &Config,
+        shape: &Self::Shape,
+        depth: f32,
+        scale: f32,
+        color: Rgba<f32>,
+    ) -> Self::Object;
+
+    /// Moves the object by `distance` through the section
+    /// and rotates it by `angle` scaled by its angular velocity.
+    fn drift(&self, object: &mut Self::Object, distance: f32, angle: f32);
+
+    /// Adds a newly spawned object.
+    fn add_object(&mut self, object: Self::Object) {
+        self.objects_mut().push(object);
+    }
+
+    /// The closest object to the section whose center is yet to cross it.
+    fn next_approaching(&self) -> Option<&Self::Object> {
+        self.objects()
+            .iter()
+            .filter(|obj| self.depth(obj) < 0.0)
+            .max_by(|a, b| self.depth(a).total_cmp(&self.depth(b)))
+    }
+
+    /// Spawns new objects behind the section, moves all objects through it,
+    /// and deletes the ones far past it.
+    fn update_objects(&mut self, config: &Config, delta_time: f32) {
+        let mut rng = thread_rng();
+        let count = self
+            .objects()
+            .iter()
+            .filter(|obj| self.depth(obj) < 0.0)
+            .count();
+        if (count as f32) < config.object_limit.value() / 100.0 * Self::MAX_APPROACHING {
+            if let Some(shape) = self
+                .prefabs()
+                .iter()
+                .filter(|prefab| prefab.is_active())
+                .filter_map(Prefab::shape)
+                .choose(&mut rng)
+                .cloned()
+            {
+                let scale = rng.gen_range(config.scale_min..=config.scale_max);
+                let color = config
+                    .object_colors
+                    .choose(&mut rng)
+                    .copied()
+                    .unwrap_or(Rgba::WHITE);
+
+                // Try a few positions that do not overlap other objects
+                let object = std::iter::repeat_with(|| {
+                    self.spawn(config, &shape, -scale * 2.0, scale, color)
+                })
+                .take(5)
+                .find(|new| {
+                    self.objects().iter().all(|obj| {
+                        Self::distance(new, obj) >= (scale + Self::scale(obj)) * Self::SPACING
+                    })
+                });
+                if let Some(object) = object {
+                    self.add_object(object);
+                }
+            }
+        }
+
+        let distance = config.speed.value() * delta_time;
+        let angle = config.rotation_speed_degrees.value().to_radians() * delta_time;
+        let mut objects = std::mem::take(self.objects_mut());
+        for obj in &mut objects {
+            self.drift(obj, distance, angle);
+        }
+        // Delete far objects
+        objects.retain(|obj| self.depth(obj) < self.despawn_depth(obj));
+        *self.objects_mut() = objects;
+    }
+
+    fn handle_event(&mut self, event: &geng::Event) {
+        if let Some(action) = self.pointer_mut().handle_event(event) {
+            self.pointer_action(action);
+        }
+    }
+
+    /// Reacts to the pointer after it has been updated.
+    /// By default, clicks toggle the prefabs.
+    fn pointer_action(&mut self, action: PointerAction) {
+        if action == PointerAction::Click {
+            self.click_prefab(self.pointer().cursor_pos);
+        }
+    }
+
+    /// Toggles the prefab with the checkbox at the given position.
+    fn click_prefab(&mut self, pos: vec2<f32>) {
+        if let Some(prefab) = self
+            .prefabs_mut()
+            .iter_mut()
+            .find(|prefab| prefab.ui_checkbox.contains(pos))
+        {
+            prefab.set_active(!prefab.is_active());
+        }
+    }
+}
+
+/// Applies `f` to every item, in parallel where threads are available.
+pub fn par_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// A value together with the inputs it was computed from.
+pub struct Cached<K, T> {
+    key: K,
+    pub value: T,
+}
+
+/// Recomputes the cached values of the objects whose inputs have changed since the last time.
+///
+/// `input` gives the current key of an object and the shape to compute from.
+/// The outdated values are computed by `compute` in parallel where threads are available,
+/// and then completed by `finish` one by one, e.g. to upload the geometry to the GPU.
+pub fn update_cached<O, S: Sync + ?Sized, K: PartialEq + Sync, U: Send, T>(
+    objects: &mut [O],
+    input: impl Fn(&O) -> (K, &S),
+    cache: impl Fn(&mut O) -> &mut Option<Cached<K, T>>,
+    compute: impl Fn(&S, &K) -> U + Sync + Send,
+    mut finish: impl FnMut(&O, U) -> T,
+) {
+    let outdated: Vec<(usize, K)> = objects
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, obj)| {
+            let (key, _) = input(obj);
+            let fresh = cache(obj).as_ref().is_some_and(|cached| cached.key == key);
+            (!fresh).then_some((i, key))
+        })
+        .collect();
+    let inputs: Vec<(&S, &K)> = outdated
+        .iter()
+        .map(|(i, key)| (input(&objects[*i]).1, key))
+        .collect();
+    let values = par_map(&inputs, |&(shape, key)| compute(shape, key));
+    for ((i, key), value) in outdated.into_iter().zip(values) {
+        let value = finish(&objects[i], value);
+        *cache(&mut objects[i]) = Some(Cached { key, value });
+    }
+}
+
+/// Draws the checkboxes toggling the prefabs in the lower right corner.
+pub fn draw_prefab_ui<T>(
+    prefabs: &mut [Prefab<T>],
+    cursor_pos: vec2<f32>,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+) {
+    let framebuffer_size = framebuffer.size().as_f32();
+    let font_size = framebuffer_size.x.min(framebuffer_size.y) * 0.02;
+    let font_size = font_size.max(20.0);
+    let camera = &geng::PixelPerfectCamera;
+
+    let checkbox_size = vec2::splat(1.5) * font_size;
+    let checkbox = Aabb2::ZERO.extend_symmetric(checkbox_size / 2.0);
+    let pos = vec2(0.9, 0.1) * framebuffer_size;
+    for (i, prefab) in prefabs.iter_mut().enumerate() {
+        prefab.ui_checkbox =
+            checkbox.translate(pos + vec2(0.0, i as f32 * (checkbox.height() + font_size * 0.5)));
+    }
+
+    for prefab in prefabs.iter() {
+        // Outline
+        let color = if prefab.active {
+            Rgba::try_from("#aaa").unwrap()
+        } else {
+            Rgba::try_from("#555").unwrap()
+        };
+        geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::Quad::new(prefab.ui_checkbox, color),
+        );
+
+        // Fill
+        let color = if prefab.ui_checkbox.contains(cursor_pos) {
+            // Hovered
+            Rgba::try_from("#333").unwrap()
+        } else {
+            Rgba::try_from("#222").unwrap()
+        };
+        geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::Quad::new(prefab.ui_checkbox.extend_uniform(-font_size * 0.2), color),
+        );
+
+        // Text
+        geng.default_font().draw(
+            framebuffer,
+            camera,
+            &prefab.name,
+            vec2::splat(geng::TextAlign::RIGHT),
+            mat3::translate(
+                geng_utils::layout::aabb_pos(prefab.ui_checkbox, vec2(0.0, 0.5))
+                    + vec2(-font_size * 0.5, -font_size / 4.0),
+            ) * mat3::scale_uniform(font_size),
+            Rgba::WHITE,
+        );
+    }
+}
+
+/// Draws a button with the text, highlighting it when it is `active` or hovered.
+pub fn draw_button(
+    ui: Aabb2<f32>,
+    text: &str,
+    active: bool,
+    cursor_pos: vec2<f32>,
+    font_size: f32,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+) {
+    let camera = &geng::PixelPerfectCamera;
+    let color = if active {
+        Rgba::try_from("#aaa").unwrap()
+    } else {
+        Rgba::try_from("#555").unwrap()
+    };
+    geng.draw2d()
+        .draw2d(framebuffer, camera, &draw2d::Quad::new(ui, color));
+    let color = if ui.contains(cursor_pos) {
+        // Hovered
+        Rgba::try_from("#333").unwrap()
+    } else {
+        Rgba::try_from("#222").unwrap()
+    };
+    geng.draw2d().draw2d(
+        framebuffer,
+        camera,
+        &draw2d::Quad::new(ui.extend_uniform(-font_size * 0.1), color),
+    );
+    let font_size = font_size * 0.8;
+    geng.default_font().draw(
+        framebuffer,
+        camera,
+        text,
+        vec2::splat(geng::TextAlign::CENTER),
+        mat3::translate(ui.center() + vec2(0.0, -font_size / 4.0)) * mat3::scale_uniform(font_size),
+        Rgba::WHITE,
+    );
+}
+
+/// Maximum number of samples kept in the [Plot].
+const PLOT_SAMPLES: usize = 4096;
+
+/// Graph of the measurements of the selected object's section over the simulation time.
+pub struct Plot {
+    /// Names of the measured quantities.
+    quantities: [&'static str; 3],
+    /// Index of the plotted quantity.
+    quantity: usize,
+    /// Id of the measured object.
+    object: Option<usize>,
+    /// Simulation time and the values of all quantities.
+    samples: VecDeque<(f32, [f32; 3])>,
+    /// Ids and screen positions of the sections in the last frame, to select them by clicking.
+    targets: Vec<(usize, vec2<f32>)>,
+    pick_radius: f32,
+    ui_quantities: [Aabb2<f32>; 3],
+    pub ui_next: Aabb2<f32>,
+}
+
+impl Plot {
+    pub fn new(quantities: [&'static str; 3]) -> Self {
+        Self {
+            quantities,
+            quantity: 0,
+            object: None,
+            samples: VecDeque::new(),
+            targets: Vec::new(),
+            pick_radius: 0.0,
+            ui_quantities: [Aabb2::ZERO; 3],
+            ui_next: Aabb2::ZERO,
+        }
+    }
+
+    /// Id of the measured object.
+    pub fn object(&self) -> Option<usize> {
+        self.object
+    }
+
+    /// Starts measuring another object.
+    pub fn select(&mut self, object: Option<usize>) {
+        if self.object != object {
+            self.object = object;
+            self.samples.clear();
+        }
+    }
+
+    /// Adds the values of the quantities at the given time.
+    pub fn record(&mut self, time: f32, values: [f32; 3]) {
+        if self.samples.back().is_some_and(|&(last, _)| time <= last) {
+            // Paused
+            return;
+        }
+        if self.samples.len() >= PLOT_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((time, values));
+    }
+
+    /// Sets the screen positions of the sections, which can be clicked to select the object.
+    pub fn set_targets(&mut self, targets: Vec<(usize, vec2<f32>)>) {
+        self.targets = targets;
+    }
+
+    /// Switches the quantity or selects the object under the cursor.
+    /// Returns whether the click was used.
+    pub fn click(&mut self, pos: vec2<f32>) -> bool {
+        if let Some(i) = self.ui_quantities.iter().position(|ui| ui.contains(pos)) {
+            self.quantity = i;
+            return true;
+        }
+        let target = self
+            .targets
+            .iter()
+            .map(|&(id, target)| (id, (target - pos).len()))
+            .filter(|&(_, distance)| distance < self.pick_radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((id, _)) = target {
+            self.select(Some(id));
+            return true;
+        }
+        false
+    }
+
+    /// Draws the panel with the graph in the lower left corner.
+    pub fn draw(
+        &mut self,
+        cursor_pos: vec2<f32>,
+        geng: &Geng,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let framebuffer_size = framebuffer.size().as_f32();
+        let font_size = framebuffer_size.x.min(framebuffer_size.y) * 0.02;
+        let font_size = font_size.max(20.0);
+        let camera = &geng::PixelPerfectCamera;
+        self.pick_radius = font_size * 3.0;
+
+        let panel =
+            Aabb2::point(vec2::splat(font_size)).extend_positive(vec2(20.0, 12.0) * font_size);
+        geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::Quad::new(panel, Rgba::try_from("#111").unwrap()),
+        );
+
+        // Buttons along the top
+        let button_size = vec2(4.5, 1.5) * font_size;
+        let button = Aabb2::ZERO.extend_positive(button_size);
+        let pos = geng_utils::layout::aabb_pos(panel, vec2(0.0, 1.0))
+            + vec2(font_size * 0.5, -font_size * 0.5 - button_size.y);
+        for (i, ui) in self.ui_quantities.iter_mut().enumerate() {
+            *ui = button.translate(pos + vec2(i as f32 * (button_size.x + font_size * 0.3), 0.0));
+        }
+        self.ui_next = button.translate(pos + vec2(3.0 * (button_size.x + font_size * 0.3), 0.0));
+        let buttons = self
+            .quantities
+            .iter()
+            .zip(self.ui_quantities)
+            .enumerate()
+            .map(|(i, (&text, ui))| (text, ui, i == self.quantity))
+            .chain([("Next", self.ui_next, false)]);
+        for (text, ui, active) in buttons {
+            draw_button(ui, text, active, cursor_pos, font_size, geng, framebuffer);
+        }
+
+        // Current value
+        let text = match (self.object, self.samples.back()) {
+            (None, _) => "Click a section to plot it".to_string(),
+            (Some(_), None) => self.quantities[self.quantity].to_string(),
+            (Some(_), Some((_, values))) => {
+                format!(
+                    "{} {}",
+                    self.quantities[self.quantity],
+                    round_hundredths(values[self.quantity])
+                )
+            }
+        };
+        geng.default_font().draw(
+            framebuffer,
+            camera,
+            &text,
+            vec2::splat(geng::TextAlign::LEFT),
+            mat3::translate(pos + vec2(0.0, -font_size * 1.5)) * mat3::scale_uniform(font_size),
+            Rgba::WHITE,
+        );
+
+        // Axes
+        let graph = Aabb2 {
+            min: panel.min + vec2::splat(font_size),
+            max: vec2(panel.max.x - font_size, pos.y - font_size * 2.5),
+        };
+        let axis_color = Rgba::try_from("#555").unwrap();
+        let axis_width = font_size * 0.05;
+        for axis in [
+            Aabb2::point(graph.min).extend_positive(vec2(graph.width(), axis_width)),
+            Aabb2::point(graph.min).extend_positive(vec2(axis_width, graph.height())),
+        ] {
+            geng.draw2d()
+                .draw2d(framebuffer, camera, &draw2d::Quad::new(axis, axis_color));
+        }
+
+        // Values scaled to fit the graph, the vertical axis starts at zero
+        let (Some(&(start, _)), Some(&(end, _))) = (self.samples.front(), self.samples.back())
+        else {
+            return;
+        };
+        let max = self
+            .samples
+            .iter()
+            .map(|(_, values)| values[self.quantity])
+            .fold(0.0, f32::max);
+        if self.samples.len() < 2 || end <= start || max <= 0.0 {
+            return;
+        }
+        let chain: Vec<vec2<f32>> = self
+            .samples
+            .iter()
+            .map(|&(time, values)| {
+                let t = vec2((time - start) / (end - start), values[self.quantity] / max);
+                graph.min + t * graph.size()
+            })
+            .collect();
+        geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::Chain::new(Chain::new(chain), font_size * 0.1, Rgba::WHITE, 5),
+        );
+        geng.default_font().draw(
+            framebuffer,
+            camera,
+            &round_hundredths(max).to_string(),
+            vec2::splat(geng::TextAlign::LEFT),
+            mat3::translate(
+                geng_utils::layout::aabb_pos(graph, vec2(0.0, 1.0))
+                    + vec2(font_size * 0.3, -font_size * 0.8),
+            ) * mat3::scale_uniform(font_size * 0.6),
+            Rgba::try_from("#aaa").unwrap(),
+        );
+    }
+}
+
+/// Rounds the value to two decimal places for display.
+fn round_hundredths(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
@@ -0,0 +1,353 @@
+use crate::{
+    geometry::{
+        hyperplane::{merge_intervals, Hyperplane, SimplexMesh},
+        VecN,
+    },
+    mode::{draw_prefab_ui, DimensionMode, Pointer},
+    Config,
+};
+
+use geng::prelude::*;
+use geng_utils::{conversions::Vec2RealConversions, texture as texture_utils};
+
+pub type Prefab = crate::mode::Prefab<Rc<SimplexMesh<2>>>;
+
+/// How much the section is enlarged when it is shown without the flat view.
+const SECTION_ZOOM: f32 = 2.0;
+
+pub struct Object {
+    pub mesh: Rc<SimplexMesh<2>>,
+    pub position: vec2<f32>,
+    /// Rotation angle in radians.
+    pub rotation: f32,
+    /// Angular speed relative to the rotation speed from the config.
+    pub angular_velocity: f32,
+    pub scale: f32,
+    pub color: Rgba<f32>,
+}
+
+impl Object {
+    pub fn new(position: vec2<f32>, mesh: Rc<SimplexMesh<2>>) -> Self {
+        Self {
+            mesh,
+            position,
+            rotation: 0.0,
+            angular_velocity: 0.0,
+            scale: 1.0,
+            color: Rgba::WHITE,
+        }
+    }
+
+    pub fn matrix(&self) -> mat3<f32> {
+        mat3::translate(self.position)
+            * mat3::rotate(Angle::from_radians(self.rotation))
+            * mat3::scale_uniform(self.scale)
+    }
+
+    /// Mesh of the object in world space.
+    pub fn transformed(&self) -> SimplexMesh<2> {
+        let matrix = self.matrix();
+        SimplexMesh {
+            vertices: self
+                .mesh
+                .vertices
+                .iter()
+                .map(|&v| (matrix * vec2::from(v).extend(1.0)).into_2d().into())
+                .collect(),
+            simplices: self.mesh.simplices.clone(),
+        }
+    }
+}
+
+/// Polygons drifting through a line.
+/// The section is a set of segments on the line.
+pub struct State1d {
+    geng: Geng,
+    flat_texture: ugli::Texture,
+    section_texture: ugli::Texture,
+    camera2d: Camera2d,
+    cross_line: Hyperplane<2>,
+    prefabs: Vec<Prefab>,
+    objects: Vec<Object>,
+    pointer: Pointer,
+}
+
+impl State1d {
+    pub fn new(geng: Geng, config: &Config) -> Self {
+        let prefab = |name: &str, mesh: SimplexMesh<2>| Prefab::new(name, true, Rc::new(mesh));
+        Self {
+            flat_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
+            section_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
+            camera2d: Camera2d {
+                center: vec2::ZERO,
+                rotation: Angle::ZERO,
+                fov: 10.0,
+            },
+            cross_line: Hyperplane {
+                normal: VecN::unit(1),
+                offset: 0.0,
+            },
+            prefabs: vec![
+                prefab("Triangle", crate::geometry::shape::unit_polygon_filled(3)),
+                prefab("Square", crate::geometry::shape::unit_polygon_filled(4)),
+                prefab("Pentagon", crate::geometry::shape::unit_polygon_filled(5)),
+                prefab("Hexagon", crate::geometry::shape::unit_polygon_filled(6)),
+                prefab(
+                    "Circle",
+                    crate::geometry::shape::unit_polygon_filled(config.shape_resolution),
+                ),
+                prefab("Star", crate::geometry::shape::unit_star(5, 0.4)),
+            ],
+            objects: Vec::new(),
+            pointer: Pointer::default(),
+            geng,
+        }
+    }
+
+    fn random_spawn(&self, distance: f32) -> vec2<f32> {
+        let mut rng = thread_rng();
+        let x = rng.gen_range(-1.0..=1.0);
+        let pos = (self
+            .camera2d
+            .projection_matrix(self.flat_texture.size().as_f32())
+            * self.camera2d.view_matrix())
+        .inverse()
+            * vec3(x, 0.0, 1.0);
+        vec2(pos.into_2d().x, distance)
+    }
+
+    pub fn update(&mut self, config: &Config, delta_time: f64) {
+        let delta_time = delta_time as f32;
+        self.update_objects(config, delta_time);
+    }
+
+    pub fn draw(&mut self, config: &Config, include_2d: bool, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+
+        let framebuffer_size = framebuffer.size().as_f32();
+
+        // Layout: the flat view on top, and the section below it, with the same horizontal scale.
+        // The flat texture keeps its size even when hidden, since it defines the spawn area.
+        let flat_pos = Aabb2::point(vec2(0.0, framebuffer_size.y * 0.4))
+            .extend_positive(framebuffer_size * vec2(1.0, 0.6));
+        let section_pos = if include_2d {
+            Aabb2::ZERO.extend_positive(framebuffer_size * vec2(1.0, 0.4))
+        } else {
+            Aabb2::ZERO.extend_positive(framebuffer_size)
+        };
+
+        // Update textures
+        let texture_size = |pos: Aabb2<f32>| pos.size().map(|x| (x.round() as usize).max(1));
+        texture_utils::update_texture_size(
+            &mut self.flat_texture,
+            texture_size(flat_pos),
+            self.geng.ugli(),
+        );
+        texture_utils::update_texture_size(
+            &mut self.section_texture,
+            texture_size(section_pos),
+            self.geng.ugli(),
+        );
+
+        // Calculate a cross section
+        let meshes: Vec<SimplexMesh<2>> = self.objects.iter().map(Object::transformed).collect();
+        let project = self.cross_line.local_projection::<1>();
+        let cross_sections: Vec<(usize, Vec<[f32; 2]>)> = meshes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, mesh)| {
+                let section = self.cross_line.slice(mesh);
+                let segments: Vec<[f32; 2]> = section
+                    .simplices
+                    .iter()
+                    .map(|segment| {
+                        let [a, b] =
+                            [segment[0], segment[1]].map(|v| project(section.vertices[v])[0]);
+                        [a.min(b), a.max(b)]
+                    })
+                    .collect();
+                // Parts of the same object touch or overlap, show them as one
+                let segments = merge_intervals(segments);
+                (!segments.is_empty()).then_some((i, segments))
+            })
+            .collect();
+
+        // Draw the full picture in 2d
+        if include_2d {
+            let mut flat_buffer =
+                texture_utils::attach_texture(&mut self.flat_texture, self.geng.ugli());
+            ugli::clear(&mut flat_buffer, Some(config.background_color), None, None);
+            for (obj, mesh) in self.objects.iter().zip(&meshes) {
+                let color = Rgba {
+                    a: 0.5,
+                    ..obj.color
+                };
+                for triangle in &mesh.simplices {
+                    let vertices = triangle.iter().map(|&i| mesh.vertices[i].into()).collect();
+                    self.geng.draw2d().draw2d(
+                        &mut flat_buffer,
+                        &self.camera2d,
+                        &draw2d::Polygon::new(vertices, color),
+                    );
+                }
+            }
+
+            // The line and the section on it
+            let half_width = self.camera2d.fov / 2.0 * flat_pos.width() / flat_pos.height();
+            self.geng.draw2d().draw2d(
+                &mut flat_buffer,
+                &self.camera2d,
+                &draw2d::Quad::new(
+                    Aabb2::from_corners(vec2(-half_width, -0.025), vec2(half_width, 0.025)),
+                    Rgba::try_from("#aaa").unwrap(),
+                ),
+            );
+            for (i, segments) in &cross_sections {
+                for &[a, b] in segments {
+                    self.geng.draw2d().draw2d(
+                        &mut flat_buffer,
+                        &self.camera2d,
+                        &draw2d::Quad::new(
+                            Aabb2::from_corners(vec2(a, -0.05), vec2(b, 0.05)),
+                            self.objects[*i].color,
+                        ),
+                    );
+                }
+            }
+            draw_texture_to(&self.flat_texture, flat_pos, &self.geng, framebuffer);
+        }
+
+        // Draw the section in 1d, with the same horizontal scale as the flat view above it,
+        // or enlarged when the flat view is hidden
+        let zoom = if include_2d { 1.0 } else { SECTION_ZOOM };
+        let section_camera = Camera2d {
+            fov: self.camera2d.fov * section_pos.height() / flat_pos.height() / zoom,
+            ..self.camera2d
+        };
+        let mut section_buffer =
+            texture_utils::attach_texture(&mut self.section_texture, self.geng.ugli());
+        ugli::clear(
+            &mut section_buffer,
+            Some(config.background_color),
+            None,
+            None,
+        );
+        let bar_height = section_camera.fov * 0.1;
+        for (i, segments) in &cross_sections {
+            for &[a, b] in segments {
+                self.geng.draw2d().draw2d(
+                    &mut section_buffer,
+                    &section_camera,
+                    &draw2d::Quad::new(
+                        Aabb2::from_corners(vec2(a, -bar_height), vec2(b, bar_height)),
+                        self.objects[*i].color,
+                    ),
+                );
+            }
+        }
+        draw_texture_to(&self.section_texture, section_pos, &self.geng, framebuffer);
+
+        // UI
+        if include_2d {
+            // Separator
+            let separator = Aabb2::point(vec2(0.0, flat_pos.min.y))
+                .extend_symmetric(vec2(0.0, 5.0))
+                .extend_right(framebuffer_size.x);
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &draw2d::Quad::new(separator, Rgba::try_from("#222").unwrap()),
+            );
+        }
+
+        // Checkboxes for different shapes
+        draw_prefab_ui(
+            &mut self.prefabs,
+            self.pointer.cursor_pos,
+            &self.geng,
+            framebuffer,
+        );
+    }
+}
+
+impl DimensionMode for State1d {
+    type Shape = Rc<SimplexMesh<2>>;
+    type Object = Object;
+
+    const MAX_APPROACHING: f32 = 15.0;
+    const SPACING: f32 = 1.0;
+
+    fn prefabs(&self) -> &[Prefab] {
+        &self.prefabs
+    }
+
+    fn prefabs_mut(&mut self) -> &mut [Prefab] {
+        &mut self.prefabs
+    }
+
+    fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    fn objects_mut(&mut self) -> &mut Vec<Object> {
+        &mut self.objects
+    }
+
+    fn pointer(&self) -> &Pointer {
+        &self.pointer
+    }
+
+    fn pointer_mut(&mut self) -> &mut Pointer {
+        &mut self.pointer
+    }
+
+    fn scale(object: &Object) -> f32 {
+        object.scale
+    }
+
+    fn distance(a: &Object, b: &Object) -> f32 {
+        (a.position - b.position).len()
+    }
+
+    fn depth(&self, object: &Object) -> f32 {
+        self.cross_line.distance(object.position.into())
+    }
+
+    fn spawn(
+        &self,
+        _config: &Config,
+        mesh: &Rc<SimplexMesh<2>>,
+        depth: f32,
+        scale: f32,
+        color: Rgba<f32>,
+    ) -> Object {
+        let mut rng = thread_rng();
+        let mut obj = Object::new(self.random_spawn(depth), mesh.clone());
+        obj.rotation = rng.gen_range(0.0..f32::PI * 2.0);
+        obj.angular_velocity = rng.gen_range(-1.0..=1.0);
+        obj.scale = scale;
+        obj.color = color;
+        obj
+    }
+
+    fn drift(&self, object: &mut Object, distance: f32, angle: f32) {
+        object.position += vec2::UNIT_Y * distance;
+        object.rotation += object.angular_velocity * angle;
+    }
+}
+
+fn draw_texture_to(
+    texture: &ugli::Texture,
+    target: Aabb2<f32>,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+) {
+    texture_utils::draw_texture_fit(
+        texture,
+        target,
+        vec2(0.5, 0.5),
+        &geng::PixelPerfectCamera,
+        geng,
+        framebuffer,
+    );
+}
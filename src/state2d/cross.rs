@@ -1,13 +1,29 @@
 use crate::{
     camera3d::Camera3d,
-    geometry::{plane::PlaneSectionVertex, Vertex},
+    geometry::{plane::PlaneSection, Vertex},
     Assets,
 };
 
 use geng::prelude::*;
 
+/// Triangulated cross section in world space, to be drawn by [draw_cross_section].
+pub fn section_geometry(cross_section: &PlaneSection, geng: &Geng) -> ugli::VertexBuffer<Vertex> {
+    let geometry = cross_section
+        .triangulate()
+        .into_iter()
+        .flatten()
+        .map(|v| Vertex {
+            a_pos: v.world_pos,
+            a_normal: vec3::ZERO,
+            a_color: Rgba::WHITE,
+        })
+        .collect();
+    ugli::VertexBuffer::new_dynamic(geng.ugli(), geometry)
+}
+
 pub fn draw_cross_section(
-    cross_section: &[PlaneSectionVertex],
+    cross_section: &PlaneSection,
+    geometry: &ugli::VertexBuffer<Vertex>,
     color: Rgba<f32>,
     camera: &Camera3d,
     assets: &Assets,
@@ -16,21 +32,11 @@ pub fn draw_cross_section(
 ) {
     let framebuffer_size = framebuffer.size().map(|x| x as f32);
 
-    let geometry = cross_section
-        .iter()
-        .map(|v| Vertex {
-            a_pos: v.world_pos,
-            a_normal: vec3::ZERO,
-            a_color: Rgba::WHITE,
-        })
-        .collect();
-    let geometry = ugli::VertexBuffer::new_dynamic(geng.ugli(), geometry);
-
     ugli::draw(
         framebuffer,
         &assets.cross.get(),
-        ugli::DrawMode::TriangleFan,
-        &geometry,
+        ugli::DrawMode::Triangles,
+        geometry,
         (
             ugli::uniforms! {
                 u_model_matrix: mat4::identity(),
@@ -47,7 +53,7 @@ pub fn draw_cross_section(
 
     // Highlight the vertices
     let vertex_geometry = geng_utils::geometry::unit_quad_geometry(geng.ugli());
-    for v in cross_section {
+    for v in cross_section.vertices() {
         let matrix = mat4::translate(v.world_pos) * mat4::scale_uniform(0.1);
         ugli::draw(
             framebuffer,
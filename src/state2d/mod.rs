@@ -4,59 +4,109 @@ mod cut;
 use crate::{
     camera3d::Camera3d,
     geometry::{
-        plane::{Plane, PlaneSectionVertex},
-        shape::Triangle,
-        Vertex,
+        plane::{AreaMeasurements, Plane, PlaneSection},
+        sdf::{self, Sdf3d},
+        shape::Mesh3d,
+        Quaternion, Vertex,
     },
+    mode::{draw_prefab_ui, update_cached, Cached, DimensionMode, Plot, Pointer, PointerAction},
     Assets, Config,
 };
 
+use std::sync::Arc;
+
 use geng::prelude::*;
-use geng_utils::{conversions::Vec2RealConversions, key as key_utils, texture as texture_utils};
+use geng_utils::{conversions::Vec2RealConversions, texture as texture_utils};
+
+/// Geometry of an object used to calculate its cross sections.
+#[derive(Clone)]
+pub enum Shape {
+    Mesh(Arc<Mesh3d>),
+    Sdf(Arc<dyn Sdf3d>),
+}
+
+impl Shape {
+    pub fn cross_sect(
+        &self,
+        plane: &Plane,
+        matrix: mat4<f32>,
+        sdf_resolution: usize,
+    ) -> PlaneSection {
+        match self {
+            Self::Mesh(mesh) => plane.cross_sect(mesh, matrix),
+            Self::Sdf(sdf) => plane.cross_sect_sdf(sdf.as_ref(), matrix, sdf_resolution),
+        }
+    }
+}
 
-pub struct Prefab {
-    pub name: String,
-    pub active: bool,
-    pub ui_checkbox: Aabb2<f32>,
+/// Shape of a prefab together with its full geometry for the 3d view.
+#[derive(Clone)]
+pub struct Model {
+    pub shape: Shape,
     pub geometry: Rc<ugli::VertexBuffer<Vertex>>,
 }
 
+pub type Prefab = crate::mode::Prefab<Model>;
+
+/// Inputs of a cross section, it is recomputed when any of them changes.
+#[derive(Debug, Clone, PartialEq)]
+struct SectionKey {
+    matrix: mat4<f32>,
+    plane: Plane,
+    sdf_resolution: usize,
+}
+
+/// Cross section of an object with its measurements and triangulated geometry in 3d.
+pub struct CachedSection {
+    pub section: PlaneSection,
+    pub measure: AreaMeasurements,
+    pub geometry: ugli::VertexBuffer<Vertex>,
+}
+
 pub struct Object {
+    /// Unique id, assigned when the object is added to the scene.
+    pub id: usize,
+    pub shape: Shape,
     pub geometry: Rc<ugli::VertexBuffer<Vertex>>,
     pub position: vec3<f32>,
-    pub orientation: vec3<f32>,
-    pub roll: Angle<f32>,
+    pub orientation: Quaternion,
+    /// Rotation axis scaled by the angular speed relative to the rotation speed from the config.
+    pub angular_velocity: vec3<f32>,
     pub scale: f32,
     pub color: Rgba<f32>,
+    /// The last calculated cross section.
+    section: Option<Cached<SectionKey, CachedSection>>,
 }
 
 impl Object {
-    pub fn new(position: vec3<f32>, geometry: Rc<ugli::VertexBuffer<Vertex>>) -> Self {
+    pub fn new(
+        position: vec3<f32>,
+        shape: Shape,
+        geometry: Rc<ugli::VertexBuffer<Vertex>>,
+    ) -> Self {
         Self {
+            id: 0,
+            shape,
             geometry,
             position,
-            orientation: vec3::UNIT_X,
-            roll: Angle::ZERO,
+            orientation: Quaternion::IDENTITY,
+            angular_velocity: vec3::ZERO,
             scale: 1.0,
             color: Rgba::WHITE,
+            section: None,
         }
     }
 
     pub fn matrix(&self) -> mat4<f32> {
-        let flat = vec2(self.orientation.x, self.orientation.z);
-        let rot_h = flat.arg();
-        let rot_v = vec2(flat.len(), self.orientation.y).arg();
         mat4::translate(self.position)
-            * mat4::rotate_x(self.roll)
-            * mat4::rotate_z(-rot_v)
-            * mat4::rotate_y(rot_h)
+            * self.orientation.to_mat4()
             * mat4::scale_uniform(self.scale)
     }
 
-    pub fn rotate_y(&mut self, angle: Angle<f32>) {
-        let flat = vec2(self.orientation.x, self.orientation.z);
-        let flat = flat.rotate(angle);
-        self.orientation = vec3(flat.x, self.orientation.y, flat.y);
+    /// Rotates the object around the axis of `rotation` by its length in world space.
+    pub fn rotate(&mut self, rotation: vec3<f32>) {
+        self.orientation =
+            (Quaternion::from_rotation_vector(rotation) * self.orientation).normalize();
     }
 }
 
@@ -79,23 +129,45 @@ pub struct State2d {
     lower_left_size: vec2<f32>,
     camera3d: Camera3d,
     camera2d: Camera2d,
+    cross_plane: Plane,
     simulation_time: f32,
     prefabs: Vec<Prefab>,
     objects: Vec<Object>,
-    cursor_pos: vec2<f32>,
-    touch_pos: vec2<f32>,
+    next_id: usize,
+    plot: Plot,
+    pointer: Pointer,
     separator_x: Aabb2<f32>,
     separator_y: Aabb2<f32>,
     drag: Option<Drag>,
 }
 
 impl State2d {
-    pub fn new(geng: Geng, assets: Rc<Assets>) -> Self {
-        let prefab = |name: &str, geometry| Prefab {
-            name: name.to_string(),
-            active: true,
-            ui_checkbox: Aabb2::ZERO,
-            geometry: Rc::new(ugli::VertexBuffer::new_dynamic(geng.ugli(), geometry)),
+    pub fn new(geng: Geng, assets: Rc<Assets>, config: &Config) -> Self {
+        let prefab = |name: &str, mesh: Mesh3d| {
+            Prefab::new(
+                name,
+                true,
+                Model {
+                    geometry: Rc::new(ugli::VertexBuffer::new_dynamic(
+                        geng.ugli(),
+                        mesh.to_vertices(),
+                    )),
+                    shape: Shape::Mesh(Arc::new(mesh)),
+                },
+            )
+        };
+        let sdf_prefab = |name: &str, sdf: Arc<dyn Sdf3d>| {
+            Prefab::new(
+                name,
+                true,
+                Model {
+                    geometry: Rc::new(ugli::VertexBuffer::new_dynamic(
+                        geng.ugli(),
+                        sdf::polygonize(sdf.as_ref(), config.sdf_resolution).to_vertices(),
+                    )),
+                    shape: Shape::Sdf(sdf),
+                },
+            )
         };
         Self {
             framebuffer_size: vec2(1, 1),
@@ -120,6 +192,10 @@ impl State2d {
                 rotation: Angle::ZERO,
                 fov: 10.0,
             },
+            cross_plane: Plane {
+                normal: vec3(0.0, 0.0, 1.0),
+                offset: 0.0,
+            },
             simulation_time: 0.0,
             prefabs: vec![
                 prefab("Cube", crate::geometry::shape::unit_cube_triangulated()),
@@ -131,10 +207,80 @@ impl State2d {
                     "Icosahedron",
                     crate::geometry::shape::unit_icosahedron_triangulized(),
                 ),
+                prefab(
+                    "Octahedron",
+                    crate::geometry::shape::unit_octahedron_triangulized(),
+                ),
+                prefab(
+                    "Dodecahedron",
+                    crate::geometry::shape::unit_dodecahedron_triangulized(),
+                ),
+                prefab(
+                    "Truncated icosahedron",
+                    crate::geometry::shape::wythoff_polyhedron(
+                        crate::geometry::shape::CoxeterGroup::H3,
+                        [false, true, true],
+                    ),
+                ),
+                prefab(
+                    "Sphere",
+                    crate::geometry::shape::unit_uv_sphere(config.shape_resolution),
+                ),
+                prefab(
+                    "Icosphere",
+                    crate::geometry::shape::unit_icosphere(config.shape_resolution),
+                ),
+                prefab(
+                    "Cylinder",
+                    crate::geometry::shape::unit_cylinder(config.shape_resolution),
+                ),
+                prefab(
+                    "Cone",
+                    crate::geometry::shape::unit_cone(config.shape_resolution),
+                ),
+                prefab(
+                    "Torus",
+                    crate::geometry::shape::unit_torus(config.shape_resolution),
+                ),
+                sdf_prefab(
+                    "Blob",
+                    Arc::new(sdf::SmoothUnion {
+                        parts: vec![
+                            sdf::Sphere {
+                                center: vec3(-0.4, -0.2, 0.0),
+                                radius: 0.6,
+                            },
+                            sdf::Sphere {
+                                center: vec3(0.5, -0.1, 0.2),
+                                radius: 0.45,
+                            },
+                            sdf::Sphere {
+                                center: vec3(0.0, 0.5, -0.2),
+                                radius: 0.4,
+                            },
+                        ],
+                        smoothness: 0.4,
+                    }),
+                ),
+                sdf_prefab(
+                    "Torus (SDF)",
+                    Arc::new(sdf::Torus {
+                        major_radius: 1.0,
+                        minor_radius: 0.4,
+                    }),
+                ),
+                sdf_prefab(
+                    "Menger sponge",
+                    Arc::new(sdf::MengerSponge {
+                        half_size: 1.0,
+                        iterations: 2,
+                    }),
+                ),
             ],
             objects: Vec::new(),
-            cursor_pos: vec2::ZERO,
-            touch_pos: vec2::ZERO,
+            next_id: 0,
+            plot: Plot::new(["Area", "Perimeter", "Vertices"]),
+            pointer: Pointer::default(),
             separator_x: Aabb2::ZERO,
             separator_y: Aabb2::ZERO,
             drag: None,
@@ -161,132 +307,7 @@ impl State2d {
         let delta_time = delta_time as f32;
 
         self.simulation_time += delta_time;
-        let mut rng = thread_rng();
-        let count = self
-            .objects
-            .iter()
-            .filter(|obj| obj.position.z < 0.0)
-            .count();
-        if (count as f32) < config.object_limit.value() / 100.0 * 15.0 {
-            if let Some(prefab) = self
-                .prefabs
-                .iter()
-                .filter(|prefab| prefab.active)
-                .choose(&mut rng)
-            {
-                let scale = rng.gen_range(config.scale_min..=config.scale_max);
-                let pos_z = -scale * 2.0;
-
-                let pos = 'outer: {
-                    let mut pos = self.random_spawn().extend(pos_z);
-                    for _ in 0..5 {
-                        let mut good = true;
-                        for obj in &self.objects {
-                            let dist = (pos - obj.position).len();
-                            if dist < (scale + obj.scale) * 1.74 {
-                                // Try another one
-                                pos = self.random_spawn().extend(pos_z);
-                                good = false;
-                                break;
-                            }
-                        }
-                        if good {
-                            break 'outer Some(pos);
-                        }
-                    }
-                    None
-                };
-
-                if let Some(pos) = pos {
-                    let mut obj = Object::new(pos, prefab.geometry.clone());
-                    obj.orientation = vec3(
-                        rng.gen_range(-1.0..=1.0),
-                        rng.gen_range(-1.0..=1.0),
-                        rng.gen_range(-1.0..=1.0),
-                    );
-                    obj.roll = Angle::from_degrees(rng.gen_range(0.0..=360.0));
-                    obj.scale = scale;
-                    obj.color = config
-                        .object_colors
-                        .choose(&mut rng)
-                        .copied()
-                        .unwrap_or(Rgba::WHITE);
-                    self.objects.push(obj);
-                }
-            }
-        }
-
-        for obj in &mut self.objects {
-            obj.position += vec3::UNIT_Z * config.speed.value() * delta_time;
-            obj.rotate_y(Angle::from_degrees(
-                config.rotation_speed_degrees.value() * delta_time,
-            ));
-        }
-        // Delete far objects
-        self.objects.retain(|obj| obj.position.z < 5.0);
-    }
-
-    pub fn handle_event(&mut self, event: &geng::Event) {
-        if let geng::Event::TouchMove(touch) = event {
-            self.touch_move(touch.position.as_f32());
-        }
-        if let geng::Event::CursorMove { position } = event {
-            self.touch_move(position.as_f32());
-        }
-
-        // TODO: multitouch
-        if let geng::Event::TouchStart(touch) = event {
-            self.touch_press(touch.position.as_f32());
-        }
-        if let geng::Event::TouchEnd(_) = event {
-            self.touch_release();
-        }
-
-        if key_utils::is_event_press(event, [geng::MouseButton::Left]) {
-            self.touch_press(self.cursor_pos);
-        } else if key_utils::is_event_release(event, [geng::MouseButton::Left]) {
-            self.touch_release();
-        }
-    }
-
-    fn touch_press(&mut self, pos: vec2<f32>) {
-        self.touch_pos = pos;
-        self.cursor_pos = pos;
-        if self.separator_x.contains(self.cursor_pos) {
-            self.drag = Some(Drag::X);
-        } else if self.separator_y.contains(self.cursor_pos) {
-            self.drag = Some(Drag::Y);
-        } else {
-            self.drag = None;
-        }
-    }
-
-    fn touch_move(&mut self, pos: vec2<f32>) {
-        self.cursor_pos = pos;
-        if let Some(drag) = &self.drag {
-            match drag {
-                Drag::X => {
-                    self.lower_left_size.x = self.cursor_pos.x / self.framebuffer_size.x as f32;
-                }
-                Drag::Y => {
-                    self.lower_left_size.y = self.cursor_pos.y / self.framebuffer_size.y as f32;
-                }
-            }
-        }
-    }
-
-    fn touch_release(&mut self) {
-        self.drag = None;
-        if (self.touch_pos - self.cursor_pos).len_sqr() < 1.0 {
-            // Click
-            if let Some(prefab) = self
-                .prefabs
-                .iter_mut()
-                .find(|prefab| prefab.ui_checkbox.contains(self.cursor_pos))
-            {
-                prefab.active = !prefab.active;
-            }
-        }
+        self.update_objects(config, delta_time);
     }
 
     pub fn draw(&mut self, config: &Config, include_3d: bool, framebuffer: &mut ugli::Framebuffer) {
@@ -338,27 +359,33 @@ impl State2d {
         );
 
         // Calculate a cross section
-        let cross_plane = Plane {
-            normal: vec3(0.0, 0.0, 1.0),
-            offset: 0.0,
-        };
-        let cross_sections: Vec<(usize, Vec<PlaneSectionVertex>)> = self
+        self.update_sections(config);
+        let cross_sections: Vec<(usize, &CachedSection)> = self
             .objects
             .iter()
             .enumerate()
-            .flat_map(|(i, obj)| {
-                let a = obj.geometry.iter().step_by(3);
-                let b = obj.geometry.iter().skip(1).step_by(3);
-                let c = obj.geometry.iter().skip(2).step_by(3);
-                let transform = |v: vec3<f32>| (obj.matrix() * v.extend(1.0)).into_3d();
-                let triangles = itertools::izip![a, b, c].map(|(a, b, c)| {
-                    Triangle::new([transform(a.a_pos), transform(b.a_pos), transform(c.a_pos)])
-                });
-                let cross_section = cross_plane.cross_sect(triangles);
-                (cross_section.len() >= 3).then_some((i, cross_section))
+            .filter_map(|(i, obj)| {
+                let cached = &obj.section.as_ref()?.value;
+                (!cached.section.is_empty()).then_some((i, cached))
             })
             .collect();
 
+        // Measure the selected object, zero while it does not touch the plane
+        if let Some(id) = self.plot.object() {
+            if let Some(i) = self.objects.iter().position(|obj| obj.id == id) {
+                let values = cross_sections
+                    .iter()
+                    .find(|(j, _)| *j == i)
+                    .map(|(_, cached)| {
+                        let measure = cached.measure;
+                        let vertices = cached.section.vertices().count();
+                        [measure.area, measure.perimeter, vertices as f32]
+                    })
+                    .unwrap_or_default();
+                self.plot.record(self.simulation_time, values);
+            }
+        }
+
         // Render
         if include_3d {
             // Cut out the part in front of the plane
@@ -371,7 +398,7 @@ impl State2d {
             for obj in &self.objects {
                 cut::draw_cut(
                     obj,
-                    cross_plane.matrix(),
+                    self.cross_plane.matrix(),
                     &self.camera3d,
                     &self.assets,
                     &mut cut_buffer,
@@ -403,10 +430,11 @@ impl State2d {
                 config.background_color,
                 self.geng.ugli(),
             );
-            for (i, cross_section) in &cross_sections {
+            for (i, cached) in &cross_sections {
                 let i = *i;
                 cross::draw_cross_section(
-                    cross_section,
+                    &cached.section,
+                    &cached.geometry,
                     self.objects[i].color,
                     &self.camera3d,
                     &self.assets,
@@ -421,22 +449,45 @@ impl State2d {
         let mut flat_buffer =
             texture_utils::attach_texture(&mut self.flat_texture, self.geng.ugli());
         ugli::clear(&mut flat_buffer, Some(config.background_color), None, None);
-        for (i, cross_section) in &cross_sections {
+        for (i, cached) in &cross_sections {
             let i = *i;
             draw_flat_section(
-                cross_section,
+                &cached.section,
                 self.objects[i].color,
                 &self.camera2d,
                 &self.geng,
                 &mut flat_buffer,
             );
         }
+        if config.show_measurements {
+            for (_, cached) in &cross_sections {
+                draw_flat_measurements(
+                    &cached.measure,
+                    &self.camera2d,
+                    &self.geng,
+                    &mut flat_buffer,
+                );
+            }
+        }
         draw_texture_to(&self.flat_texture, flat_pos, &self.geng, framebuffer);
 
-        // UI
-        let font_size = framebuffer_size.x.min(framebuffer_size.y) * 0.02;
-        let font_size = font_size.max(20.0);
+        // Positions of the sections on the screen to select them for the plot
+        let flat_matrix = self
+            .camera2d
+            .projection_matrix(self.flat_texture.size().as_f32())
+            * self.camera2d.view_matrix();
+        let targets = cross_sections
+            .iter()
+            .map(|(i, cached)| {
+                let centroid = cached.measure.centroid;
+                let pos = (flat_matrix * vec2(-centroid.x, centroid.y).extend(1.0)).into_2d();
+                let pos = flat_pos.min + (pos + vec2::splat(1.0)) / 2.0 * flat_pos.size();
+                (self.objects[*i].id, pos)
+            })
+            .collect();
+        self.plot.set_targets(targets);
 
+        // UI
         let camera = &geng::PixelPerfectCamera;
         if include_3d {
             let color_normal = Rgba::try_from("#222").unwrap();
@@ -446,7 +497,7 @@ impl State2d {
             // Horizontal
             let color = if let Some(Drag::Y) = self.drag {
                 color_drag
-            } else if self.separator_y.contains(self.cursor_pos) {
+            } else if self.separator_y.contains(self.pointer.cursor_pos) {
                 color_hover
             } else {
                 color_normal
@@ -463,7 +514,7 @@ impl State2d {
             // Vertical
             let color = if let Some(Drag::X) = self.drag {
                 color_drag
-            } else if self.separator_x.contains(self.cursor_pos) {
+            } else if self.separator_x.contains(self.pointer.cursor_pos) {
                 color_hover
             } else {
                 color_normal
@@ -479,82 +530,207 @@ impl State2d {
         }
 
         // Checkboxes for different shapes
-        {
-            let checkbox_size = vec2::splat(1.5) * font_size;
-            let checkbox = Aabb2::ZERO.extend_symmetric(checkbox_size / 2.0);
-            let pos = vec2(0.9, 0.1) * framebuffer_size;
-            for (i, prefab) in self.prefabs.iter_mut().enumerate() {
-                prefab.ui_checkbox = checkbox
-                    .translate(pos + vec2(0.0, i as f32 * (checkbox.height() + font_size * 0.5)));
-            }
+        draw_prefab_ui(
+            &mut self.prefabs,
+            self.pointer.cursor_pos,
+            &self.geng,
+            framebuffer,
+        );
 
-            for prefab in &self.prefabs {
-                // Outline
-                let color = if prefab.active {
-                    Rgba::try_from("#aaa").unwrap()
-                } else {
-                    Rgba::try_from("#555").unwrap()
-                };
-                self.geng.draw2d().draw2d(
-                    framebuffer,
-                    camera,
-                    &draw2d::Quad::new(prefab.ui_checkbox, color),
-                );
+        self.plot
+            .draw(self.pointer.cursor_pos, &self.geng, framebuffer);
+    }
 
-                // Fill
-                let color = if prefab.ui_checkbox.contains(self.cursor_pos) {
-                    // Hovered
-                    Rgba::try_from("#333").unwrap()
-                } else {
-                    Rgba::try_from("#222").unwrap()
+    /// Recalculates the cross sections of the objects that have moved since the last time.
+    fn update_sections(&mut self, config: &Config) {
+        let plane = &self.cross_plane;
+        let geng = &self.geng;
+        update_cached(
+            &mut self.objects,
+            |obj| {
+                let key = SectionKey {
+                    matrix: obj.matrix(),
+                    plane: plane.clone(),
+                    sdf_resolution: config.sdf_resolution,
                 };
-                self.geng.draw2d().draw2d(
-                    framebuffer,
-                    camera,
-                    &draw2d::Quad::new(prefab.ui_checkbox.extend_uniform(-font_size * 0.2), color),
-                );
+                (key, &obj.shape)
+            },
+            |obj| &mut obj.section,
+            |shape, key| {
+                let section = shape.cross_sect(&key.plane, key.matrix, key.sdf_resolution);
+                (section.measure(), section)
+            },
+            |_, (measure, section)| CachedSection {
+                geometry: cross::section_geometry(&section, geng),
+                measure,
+                section,
+            },
+        );
+    }
+}
 
-                // Text
-                // let font_size = font_size * 0.8;
-                self.geng.default_font().draw(
-                    framebuffer,
-                    camera,
-                    &prefab.name,
-                    vec2::splat(geng::TextAlign::RIGHT),
-                    mat3::translate(
-                        geng_utils::layout::aabb_pos(prefab.ui_checkbox, vec2(0.0, 0.5))
-                            + vec2(-font_size * 0.5, -font_size / 4.0),
-                    ) * mat3::scale_uniform(font_size),
-                    Rgba::WHITE,
-                );
+impl DimensionMode for State2d {
+    type Shape = Model;
+    type Object = Object;
+
+    const MAX_APPROACHING: f32 = 15.0;
+    const SPACING: f32 = 1.74;
+
+    fn prefabs(&self) -> &[Prefab] {
+        &self.prefabs
+    }
+
+    fn prefabs_mut(&mut self) -> &mut [Prefab] {
+        &mut self.prefabs
+    }
+
+    fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    fn objects_mut(&mut self) -> &mut Vec<Object> {
+        &mut self.objects
+    }
+
+    fn pointer(&self) -> &Pointer {
+        &self.pointer
+    }
+
+    fn pointer_mut(&mut self) -> &mut Pointer {
+        &mut self.pointer
+    }
+
+    fn pointer_action(&mut self, action: PointerAction) {
+        let pos = self.pointer.cursor_pos;
+        match action {
+            PointerAction::Press => {
+                if self.separator_x.contains(pos) {
+                    self.drag = Some(Drag::X);
+                } else if self.separator_y.contains(pos) {
+                    self.drag = Some(Drag::Y);
+                } else {
+                    self.drag = None;
+                }
+            }
+            PointerAction::Move => match self.drag {
+                Some(Drag::X) => {
+                    self.lower_left_size.x = pos.x / self.framebuffer_size.x as f32;
+                }
+                Some(Drag::Y) => {
+                    self.lower_left_size.y = pos.y / self.framebuffer_size.y as f32;
+                }
+                None => {}
+            },
+            PointerAction::Release => {
+                self.drag = None;
+            }
+            PointerAction::Click => {
+                self.drag = None;
+                if self.plot.ui_next.contains(pos) {
+                    let next = self.next_approaching().map(|obj| obj.id);
+                    self.plot.select(next);
+                } else if !self.plot.click(pos) {
+                    self.click_prefab(pos);
+                }
             }
         }
     }
+
+    fn add_object(&mut self, mut object: Object) {
+        object.id = self.next_id;
+        self.next_id += 1;
+        self.objects.push(object);
+    }
+
+    fn scale(object: &Object) -> f32 {
+        object.scale
+    }
+
+    fn distance(a: &Object, b: &Object) -> f32 {
+        (a.position - b.position).len()
+    }
+
+    fn depth(&self, object: &Object) -> f32 {
+        object.position.z
+    }
+
+    fn spawn(
+        &self,
+        _config: &Config,
+        model: &Model,
+        depth: f32,
+        scale: f32,
+        color: Rgba<f32>,
+    ) -> Object {
+        let mut rng = thread_rng();
+        let position = self.random_spawn().extend(depth);
+        let mut obj = Object::new(position, model.shape.clone(), model.geometry.clone());
+        let w = rng.gen_range(-1.0..=1.0);
+        let mut random_vector = || {
+            vec3(
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+                rng.gen_range(-1.0..=1.0),
+            )
+        };
+        obj.orientation = Quaternion::new(w, random_vector()).normalize();
+        obj.angular_velocity = random_vector().normalize_or_zero();
+        obj.scale = scale;
+        obj.color = color;
+        obj
+    }
+
+    fn drift(&self, object: &mut Object, distance: f32, angle: f32) {
+        object.position += vec3::UNIT_Z * distance;
+        object.rotate(object.angular_velocity * angle);
+    }
 }
 
 fn draw_flat_section(
-    cross_section: &[PlaneSectionVertex],
+    cross_section: &PlaneSection,
     color: Rgba<f32>,
     camera: &Camera2d,
     geng: &Geng,
     framebuffer: &mut ugli::Framebuffer,
 ) {
-    if cross_section.len() < 3 {
-        return;
+    let mirror_x = |v: vec2<f32>| vec2(-v.x, v.y);
+    for contour in &cross_section.contours {
+        if contour.vertices.len() < 3 {
+            continue;
+        }
+
+        let mut chain: Vec<vec2<f32>> = contour
+            .vertices
+            .iter()
+            .map(|v| mirror_x(v.projected))
+            .collect();
+        let mid = (chain[0] + chain[1]) / 2.0;
+        chain.extend([chain[0], mid]);
+        chain[0] = mid;
+        geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::Chain::new(Chain::new(chain), 0.1, color, 5),
+        );
     }
+}
 
-    let mirror_x = |v: vec2<f32>| vec2(-v.x, v.y);
-    let mut chain: Vec<vec2<f32>> = cross_section
-        .iter()
-        .map(|v| mirror_x(v.projected))
-        .collect();
-    let mid = (chain[0] + chain[1]) / 2.0;
-    chain.extend([chain[0], mid]);
-    chain[0] = mid;
-    geng.draw2d().draw2d(
+/// Writes the area and the perimeter of the section at its centroid.
+fn draw_flat_measurements(
+    measure: &AreaMeasurements,
+    camera: &Camera2d,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+) {
+    let text = format!("A {:.2}  P {:.2}", measure.area, measure.perimeter);
+    let pos = vec2(-measure.centroid.x, measure.centroid.y);
+    geng.default_font().draw(
         framebuffer,
         camera,
-        &draw2d::Chain::new(Chain::new(chain), 0.1, color, 5),
+        &text,
+        vec2::splat(geng::TextAlign::CENTER),
+        mat3::translate(pos) * mat3::scale_uniform(0.3),
+        Rgba::WHITE,
     );
 }
 
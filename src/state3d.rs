@@ -1,80 +1,151 @@
 use crate::{
     camera3d::Camera3d,
-    geometry::{mat5, shape::Tetrahedron4d, space::Space, vec4_len, vec4_norm, vec5, Vertex},
+    geometry::{
+        mat5,
+        sdf::{self, Sdf4d},
+        shape::{Mesh3d, Mesh4d, VolumeMeasurements},
+        space::Space,
+        vec4_len, vec4_norm, Bivector4, Quaternion, Rotor4, Vertex,
+    },
+    mode::{
+        draw_button, draw_prefab_ui, update_cached, Cached, DimensionMode, Plot, Pointer,
+        PointerAction,
+    },
     Assets, Config,
 };
 
+use std::sync::Arc;
+
 use geng::prelude::*;
 use geng_utils::{conversions::Vec2RealConversions, texture as texture_utils};
 
+/// Geometry of an object used to calculate its cross sections.
+#[derive(Clone)]
+pub enum Shape {
+    Mesh(Arc<Mesh4d>),
+    Sdf(Arc<dyn Sdf4d>),
+}
+
+impl Shape {
+    pub fn cross_sect(&self, space: &Space, matrix: mat5<f32>, sdf_resolution: usize) -> Mesh3d {
+        match self {
+            Self::Mesh(mesh) => space.cross_sect(mesh, matrix),
+            Self::Sdf(sdf) => space.cross_sect_sdf(sdf.as_ref(), matrix, sdf_resolution),
+        }
+    }
+}
+
+pub type Prefab = crate::mode::Prefab<Shape>;
+
+/// How fast the space turns towards the selected normal.
+const NORMAL_TURN_SPEED_DEGREES: f32 = 90.0;
+
+/// Normals of the space slicing the axis-aligned tesseract
+/// starting from a cell, a face, an edge or a vertex.
+fn normal_presets() -> [(&'static str, vec4<f32>); 4] {
+    [
+        ("Cell", vec4(0.0, 0.0, 0.0, 1.0)),
+        ("Face", vec4(0.0, 0.0, 1.0, 1.0)),
+        ("Edge", vec4(0.0, 1.0, 1.0, 1.0)),
+        ("Vertex", vec4(1.0, 1.0, 1.0, 1.0)),
+    ]
+}
+
+/// Inputs of a cross section, it is recomputed when any of them changes.
+#[derive(Debug, Clone, PartialEq)]
+struct SectionKey {
+    matrix: mat5<f32>,
+    space: Space,
+    sdf_resolution: usize,
+}
+
+/// Cross section of an object with its measurements and geometry to render.
+pub struct CachedSection {
+    pub section: Mesh3d,
+    pub measure: VolumeMeasurements,
+    pub geometry: ugli::VertexBuffer<Vertex>,
+}
+
 pub struct Object {
-    pub geometry: Rc<[Tetrahedron4d]>,
+    /// Unique id, assigned when the object is added to the scene.
+    pub id: usize,
+    pub shape: Shape,
     pub position: vec4<f32>,
-    pub orientation: vec4<f32>,
-    pub roll: Angle<f32>,
+    pub orientation: Rotor4,
+    /// Angular velocity relative to the rotation speed from the config.
+    pub angular_velocity: Bivector4,
     pub scale: f32,
     pub color: Rgba<f32>,
+    /// The last calculated cross section.
+    section: Option<Cached<SectionKey, CachedSection>>,
 }
 
 impl Object {
-    pub fn new(position: vec4<f32>, geometry: Rc<[Tetrahedron4d]>) -> Self {
+    pub fn new(position: vec4<f32>, shape: Shape) -> Self {
         Self {
-            geometry,
+            id: 0,
+            shape,
             position,
-            orientation: vec4::UNIT_X,
-            roll: Angle::ZERO,
+            orientation: Rotor4::IDENTITY,
+            angular_velocity: Bivector4::ZERO,
             scale: 1.0,
             color: Rgba::WHITE,
+            section: None,
         }
     }
 
     pub fn matrix(&self) -> mat5<f32> {
-        let flat = vec2(self.orientation.x, self.orientation.z);
-        let rot_xy = flat.arg();
-        let vert = vec2(flat.len(), self.orientation.y);
-        let rot_xz = vert.arg();
-        let rot_yw = vec2(vert.len(), self.orientation.w).arg();
         mat5::translate(self.position)
-            * mat5::rotate_zw(self.roll)
-            * mat5::rotate_xz(rot_xz)
-            * mat5::rotate_xy(rot_xy)
-            * mat5::rotate_yw(rot_yw)
+            * self.orientation.to_mat5()
             * mat5::scale_uniform(self.scale)
     }
 
-    pub fn rotate_zw(&mut self, angle: Angle<f32>) {
-        let flat = vec2(self.orientation.z, self.orientation.w);
-        let flat = flat.rotate(angle);
-        self.orientation.z = flat.x;
-        self.orientation.w = flat.y;
+    /// Rotates the object by the given angles in world space.
+    pub fn rotate(&mut self, angles: Bivector4) {
+        self.orientation = (Rotor4::from_bivector(angles) * self.orientation).normalize();
     }
 }
 
 pub struct State3d {
     geng: Geng,
     assets: Rc<Assets>,
-    unit_geometry: Rc<ugli::VertexBuffer<draw2d::TexturedVertex>>,
     framebuffer_size: vec2<usize>,
-    screen_texture: ugli::Texture,
-    normal_texture: ugli::Texture,
-    depth_buffer: ugli::Renderbuffer<ugli::DepthComponent>,
+    renderer: SectionRenderer,
     simulation_time: f32,
-    prefabs: Vec<Rc<[Tetrahedron4d]>>,
+    cross_space: Space,
+    /// Rotation taking the `w = 0` space into the cross space.
+    /// The sections are drawn in the rotated frame, so their axes stay continuous while it turns.
+    space_orientation: Rotor4,
+    /// The space turns towards this normal.
+    target_normal: vec4<f32>,
+    /// Orientation of the space once it has turned to the target normal.
+    target_orientation: Rotor4,
+    ui_normals: [Aabb2<f32>; 4],
+    prefabs: Vec<Prefab>,
     objects: Vec<Object>,
+    next_id: usize,
+    plot: Plot,
     camera: Camera3d,
-    paused: bool,
+    pointer: Pointer,
 }
 
 impl State3d {
-    pub fn new(geng: Geng, assets: Rc<Assets>) -> Self {
-        let prefab = |geometry: &[_]| Rc::from(geometry);
+    pub fn new(geng: Geng, assets: Rc<Assets>, config: &Config) -> Self {
+        // 4d shapes have a lot more cells per segment than 3d ones
+        let resolution = config.shape_resolution / 2;
+        let prefab = |name: &str, active: bool, geometry: Mesh4d| {
+            Prefab::new(name, active, Shape::Mesh(Arc::new(geometry)))
+        };
+        let sdf_prefab = |name: &str, active: bool, sdf: Arc<dyn Sdf4d>| {
+            Prefab::new(name, active, Shape::Sdf(sdf))
+        };
+        let lazy_prefab = |name: &str, active: bool, build: Box<dyn Fn() -> Mesh4d>| {
+            Prefab::lazy(name, active, move || Shape::Mesh(Arc::new(build())))
+        };
         Self {
-            unit_geometry: Rc::new(geng_utils::geometry::unit_quad_geometry(geng.ugli())),
             simulation_time: 0.0,
             framebuffer_size: vec2(1, 1),
-            screen_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
-            normal_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
-            depth_buffer: ugli::Renderbuffer::new(geng.ugli(), vec2(1, 1)),
+            renderer: SectionRenderer::new(&geng),
             camera: Camera3d {
                 fov: Angle::from_radians(70.0),
                 pos: vec3(0.0, 0.0, 10.0),
@@ -83,15 +154,132 @@ impl State3d {
                 near: 1.0,
                 far: 50.0,
             },
+            cross_space: Space {
+                normal: vec4::UNIT_W,
+                offset: 0.0,
+            },
+            space_orientation: Rotor4::IDENTITY,
+            target_normal: vec4::UNIT_W,
+            target_orientation: Rotor4::IDENTITY,
+            ui_normals: [Aabb2::ZERO; 4],
             objects: Vec::new(),
-            prefabs: vec![prefab(&crate::geometry::shape::unit_5cell_tetrahedralized())],
-            paused: false,
+            next_id: 0,
+            plot: Plot::new(["Volume", "Surface", "Vertices"]),
+            prefabs: vec![
+                prefab(
+                    "5-cell",
+                    true,
+                    crate::geometry::shape::unit_5cell_tetrahedralized(),
+                ),
+                prefab("Tesseract", true, crate::geometry::shape::unit_tesseract()),
+                prefab("16-cell", true, crate::geometry::shape::unit_16cell()),
+                prefab("24-cell", true, crate::geometry::shape::unit_24cell()),
+                prefab(
+                    "3-5 duoprism",
+                    true,
+                    crate::geometry::shape::unit_duoprism(3, 5),
+                ),
+                prefab(
+                    "Tetrahedral prism",
+                    true,
+                    crate::geometry::shape::unit_prism(
+                        &crate::geometry::shape::unit_tetrahedron_triangulized(),
+                    ),
+                ),
+                prefab(
+                    "Icosahedral prism",
+                    true,
+                    crate::geometry::shape::unit_prism(
+                        &crate::geometry::shape::unit_icosahedron_triangulized(),
+                    ),
+                ),
+                prefab(
+                    "Truncated 5-cell",
+                    true,
+                    crate::geometry::shape::wythoff_polychoron(
+                        crate::geometry::shape::CoxeterGroup::A4,
+                        [true, true, false, false],
+                    ),
+                ),
+                prefab(
+                    "Rectified tesseract",
+                    true,
+                    crate::geometry::shape::wythoff_polychoron(
+                        crate::geometry::shape::CoxeterGroup::B4,
+                        [false, true, false, false],
+                    ),
+                ),
+                prefab(
+                    "Glome",
+                    true,
+                    crate::geometry::shape::unit_glome(resolution),
+                ),
+                prefab(
+                    "Spherinder",
+                    true,
+                    crate::geometry::shape::unit_spherinder(resolution),
+                ),
+                prefab(
+                    "Cubinder",
+                    true,
+                    crate::geometry::shape::unit_cubinder(resolution),
+                ),
+                prefab(
+                    "Duocylinder",
+                    true,
+                    crate::geometry::shape::unit_duocylinder(resolution),
+                ),
+                sdf_prefab(
+                    "Blob",
+                    true,
+                    Arc::new(sdf::SmoothUnion {
+                        parts: vec![
+                            sdf::Glome {
+                                center: vec4(-0.4, -0.2, 0.0, 0.1),
+                                radius: 0.6,
+                            },
+                            sdf::Glome {
+                                center: vec4(0.5, -0.1, 0.2, -0.2),
+                                radius: 0.45,
+                            },
+                            sdf::Glome {
+                                center: vec4(0.0, 0.5, -0.2, 0.3),
+                                radius: 0.4,
+                            },
+                        ],
+                        smoothness: 0.4,
+                    }),
+                ),
+                // The high detail ones are disabled by default and built once enabled
+                lazy_prefab(
+                    "120-cell",
+                    false,
+                    Box::new(crate::geometry::shape::unit_120cell),
+                ),
+                lazy_prefab(
+                    "600-cell",
+                    false,
+                    Box::new(crate::geometry::shape::unit_600cell),
+                ),
+                lazy_prefab(
+                    "Tiger",
+                    false,
+                    Box::new(move || crate::geometry::shape::unit_tiger(resolution)),
+                ),
+                lazy_prefab(
+                    "Spheritorus",
+                    false,
+                    Box::new(move || crate::geometry::shape::unit_spheritorus(resolution)),
+                ),
+            ],
+            pointer: Pointer::default(),
             geng,
             assets,
         }
     }
 
-    pub fn random_spawn(&self, config: &Config) -> vec3<f32> {
+    /// Returns a random position visible on the screen at the given distance from the cross space.
+    pub fn random_spawn(&self, config: &Config, distance: f32) -> vec4<f32> {
         let mut rng = thread_rng();
         let x = rng.gen_range(-1.0..=1.0);
         let y = rng.gen_range(-1.0..=1.0);
@@ -103,118 +291,384 @@ impl State3d {
             * self.camera.view_matrix())
         .inverse()
             * pos.extend(1.0);
-        pos.into_3d()
-    }
-
-    pub fn handle_event(&mut self, event: &geng::Event) {
-        if geng_utils::key::is_event_press(event, [geng::Key::P]) {
-            self.paused = !self.paused;
-        }
+        let pos = pos.into_3d();
+        self.space_orientation.rotate(vec4(
+            pos.x,
+            pos.y,
+            pos.z,
+            self.cross_space.offset + distance,
+        ))
     }
 
     pub fn update(&mut self, config: &Config, delta_time: f64) {
         let delta_time = delta_time as f32;
 
         self.simulation_time += delta_time;
-        let mut rng = thread_rng();
-        let count = self
+        self.turn_space(delta_time);
+        self.update_objects(config, delta_time);
+    }
+
+    /// Starts turning the space towards the normal in the plane of the old and the new normals.
+    fn set_target_normal(&mut self, normal: vec4<f32>) {
+        let current = self.space_orientation.rotate(vec4::UNIT_W);
+        // Opposite normals turn towards the x axis of the space
+        let fallback = self.space_orientation.rotate(vec4::UNIT_X);
+        let rotation = Rotor4::between(current, normal, fallback);
+        self.target_normal = normal;
+        self.target_orientation = (rotation * self.space_orientation).normalize();
+    }
+
+    /// Turns the space towards the target orientation.
+    fn turn_space(&mut self, delta_time: f32) {
+        let normal = self.space_orientation.rotate(vec4::UNIT_W);
+        let angle = vec4::dot(normal, self.target_normal)
+            .clamp(-1.0, 1.0)
+            .acos();
+        let step = NORMAL_TURN_SPEED_DEGREES.to_radians() * delta_time;
+        self.space_orientation = if angle <= step {
+            self.target_orientation
+        } else {
+            // The target differs by a rotation in a single plane, so this keeps turning in it
+            self.space_orientation
+                .slerp(&self.target_orientation, step / angle)
+                .normalize()
+        };
+        self.cross_space.normal = self.space_orientation.rotate(vec4::UNIT_W);
+    }
+
+    pub fn draw(&mut self, config: &Config, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(config.background_color), Some(1.0), None);
+
+        self.framebuffer_size = framebuffer.size();
+
+        // Calculate a cross section
+        self.update_sections(config);
+        let cross_sections: Vec<(&Object, &CachedSection)> = self
             .objects
             .iter()
-            .filter(|obj| obj.position.w < 0.0)
-            .count();
-        if (count as f32) < config.object_limit.value() / 100.0 * 30.0 {
-            if let Some(geometry) = self.prefabs.choose(&mut rng) {
-                let scale = rng.gen_range(config.scale_min..=config.scale_max);
-                let pos_w = -scale * 2.0;
-
-                let pos = 'outer: {
-                    let mut pos = self.random_spawn(config).extend(pos_w);
-                    for _ in 0..5 {
-                        let mut good = true;
-                        for obj in &self.objects {
-                            let dist = vec4_len(pos - obj.position);
-                            if dist < (scale + obj.scale) * 2.0 {
-                                // Try another one
-                                pos = self.random_spawn(config).extend(pos_w);
-                                good = false;
-                                break;
-                            }
-                        }
-                        if good {
-                            break 'outer Some(pos);
-                        }
-                    }
-                    None
+            .filter_map(|obj| Some((obj, &obj.section.as_ref()?.value)))
+            .collect();
+
+        // Measure the selected object, zero while it does not touch the space
+        if let Some(id) = self.plot.object() {
+            if let Some((_, cached)) = cross_sections.iter().find(|(obj, _)| obj.id == id) {
+                let measure = cached.measure;
+                let vertices = cached.section.vertices().len();
+                let values = [measure.volume, measure.surface_area, vertices as f32];
+                self.plot.record(self.simulation_time, values);
+            }
+        }
+
+        let geometry: Vec<&ugli::VertexBuffer<Vertex>> = cross_sections
+            .iter()
+            .map(|(_, cached)| &cached.geometry)
+            .collect();
+        self.renderer.draw(
+            &geometry,
+            &self.camera,
+            &self.assets,
+            config.background_color,
+            framebuffer,
+        );
+
+        if config.show_measurements {
+            for (_, cached) in &cross_sections {
+                self.draw_measurements(&cached.measure, framebuffer);
+            }
+        }
+
+        // Positions of the sections on the screen to select them for the plot
+        let targets = cross_sections
+            .iter()
+            .filter(|(_, cached)| cached.measure.volume > 0.0)
+            .filter_map(|(obj, cached)| {
+                Some((obj.id, self.world_to_screen(cached.measure.centroid)?))
+            })
+            .collect();
+        self.plot.set_targets(targets);
+
+        self.draw_ui(framebuffer);
+    }
+
+    /// Returns the pixel position of the point in the section space,
+    /// or `None` if it is behind the camera.
+    fn world_to_screen(&self, pos: vec3<f32>) -> Option<vec2<f32>> {
+        let framebuffer_size = self.framebuffer_size.as_f32();
+        let clip = self.camera.projection_matrix(framebuffer_size)
+            * self.camera.view_matrix()
+            * pos.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        Some((vec2(clip.x, clip.y) / clip.w + vec2::splat(1.0)) / 2.0 * framebuffer_size)
+    }
+
+    /// Writes the volume and the surface area of the section over its centroid.
+    fn draw_measurements(&self, measure: &VolumeMeasurements, framebuffer: &mut ugli::Framebuffer) {
+        if measure.volume <= 0.0 {
+            return;
+        }
+        let Some(pos) = self.world_to_screen(measure.centroid) else {
+            return;
+        };
+
+        let framebuffer_size = framebuffer.size().as_f32();
+        let font_size = framebuffer_size.x.min(framebuffer_size.y) * 0.02;
+        let font_size = font_size.max(20.0);
+        let text = format!("V {:.2}  S {:.2}", measure.volume, measure.surface_area);
+        self.geng.default_font().draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            &text,
+            vec2::splat(geng::TextAlign::CENTER),
+            mat3::translate(pos) * mat3::scale_uniform(font_size),
+            Rgba::WHITE,
+        );
+    }
+
+    /// Recalculates the cross sections of the objects that have moved since the last time.
+    fn update_sections(&mut self, config: &Config) {
+        // Slice in the frame of the space, where it is orthogonal to w
+        let space = Space {
+            normal: vec4::UNIT_W,
+            offset: self.cross_space.offset,
+        };
+        let to_space = self.space_orientation.inverse().to_mat5();
+        let geng = &self.geng;
+        update_cached(
+            &mut self.objects,
+            |obj| {
+                let key = SectionKey {
+                    matrix: to_space * obj.matrix(),
+                    space: space.clone(),
+                    sdf_resolution: config.sdf_resolution,
                 };
+                (key, &obj.shape)
+            },
+            |obj| &mut obj.section,
+            |shape, key| {
+                let section = shape.cross_sect(&key.space, key.matrix, key.sdf_resolution);
+                (section.measure(), section)
+            },
+            |obj, (measure, section)| {
+                let geometry = section
+                    .to_vertices()
+                    .into_iter()
+                    .map(|v| v.colored(obj.color))
+                    .collect();
+                CachedSection {
+                    geometry: ugli::VertexBuffer::new_dynamic(geng.ugli(), geometry),
+                    measure,
+                    section,
+                }
+            },
+        );
+    }
 
-                if let Some(pos) = pos {
-                    let mut obj = Object::new(pos, geometry.clone());
-                    obj.orientation = vec4_norm(vec4(
-                        rng.gen_range(-1.0..=1.0),
+    fn draw_ui(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        // Checkboxes for different shapes
+        draw_prefab_ui(
+            &mut self.prefabs,
+            self.pointer.cursor_pos,
+            &self.geng,
+            framebuffer,
+        );
+
+        self.plot
+            .draw(self.pointer.cursor_pos, &self.geng, framebuffer);
+
+        // Buttons choosing the normal above the plot
+        let framebuffer_size = framebuffer.size().as_f32();
+        let font_size = framebuffer_size.x.min(framebuffer_size.y) * 0.02;
+        let font_size = font_size.max(20.0);
+        let button_size = vec2(4.5, 1.5) * font_size;
+        let pos = vec2(font_size, font_size * 14.0);
+        self.geng.default_font().draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            "Slice first by",
+            vec2::splat(geng::TextAlign::LEFT),
+            mat3::translate(pos + vec2(0.0, button_size.y + font_size * 0.5))
+                * mat3::scale_uniform(font_size),
+            Rgba::WHITE,
+        );
+        for (i, (ui, (name, normal))) in
+            self.ui_normals.iter_mut().zip(normal_presets()).enumerate()
+        {
+            *ui = Aabb2::point(pos + vec2(i as f32 * (button_size.x + font_size * 0.3), 0.0))
+                .extend_positive(button_size);
+            let active = vec4_norm(normal) == self.target_normal;
+            draw_button(
+                *ui,
+                name,
+                active,
+                self.pointer.cursor_pos,
+                font_size,
+                &self.geng,
+                framebuffer,
+            );
+        }
+    }
+}
+
+impl DimensionMode for State3d {
+    type Shape = Shape;
+    type Object = Object;
+
+    const MAX_APPROACHING: f32 = 30.0;
+    const SPACING: f32 = 2.0;
+
+    fn prefabs(&self) -> &[Prefab] {
+        &self.prefabs
+    }
+
+    fn prefabs_mut(&mut self) -> &mut [Prefab] {
+        &mut self.prefabs
+    }
+
+    fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+
+    fn objects_mut(&mut self) -> &mut Vec<Object> {
+        &mut self.objects
+    }
+
+    fn pointer(&self) -> &Pointer {
+        &self.pointer
+    }
+
+    fn pointer_mut(&mut self) -> &mut Pointer {
+        &mut self.pointer
+    }
+
+    fn pointer_action(&mut self, action: PointerAction) {
+        if action != PointerAction::Click {
+            return;
+        }
+        let pos = self.pointer.cursor_pos;
+        if let Some(i) = self.ui_normals.iter().position(|ui| ui.contains(pos)) {
+            self.set_target_normal(vec4_norm(normal_presets()[i].1));
+        } else if self.plot.ui_next.contains(pos) {
+            let next = self.next_approaching().map(|obj| obj.id);
+            self.plot.select(next);
+        } else if !self.plot.click(pos) {
+            self.click_prefab(pos);
+        }
+    }
+
+    fn add_object(&mut self, mut object: Object) {
+        object.id = self.next_id;
+        self.next_id += 1;
+        self.objects.push(object);
+    }
+
+    fn scale(object: &Object) -> f32 {
+        object.scale
+    }
+
+    fn distance(a: &Object, b: &Object) -> f32 {
+        vec4_len(a.position - b.position)
+    }
+
+    fn depth(&self, object: &Object) -> f32 {
+        self.cross_space.distance(object.position)
+    }
+
+    fn despawn_depth(&self, object: &Object) -> f32 {
+        // Nothing is drawn past the cross space
+        object.scale * 2.0
+    }
+
+    fn spawn(
+        &self,
+        config: &Config,
+        shape: &Shape,
+        depth: f32,
+        scale: f32,
+        color: Rgba<f32>,
+    ) -> Object {
+        let mut rng = thread_rng();
+        let mut obj = Object::new(self.random_spawn(config, depth), shape.clone());
+        // Objects that do not spin stay axis-aligned,
+        // so that the normal presets slice them starting from a cell, a face, an edge or a vertex
+        if config.rotation_speed_degrees.value() != 0.0 {
+            let mut random_quaternion = || {
+                Quaternion::new(
+                    rng.gen_range(-1.0..=1.0),
+                    vec3(
                         rng.gen_range(-1.0..=1.0),
                         rng.gen_range(-1.0..=1.0),
                         rng.gen_range(-1.0..=1.0),
-                    ));
-                    obj.roll = Angle::from_degrees(rng.gen_range(0.0..=360.0));
-                    obj.scale = scale;
-                    obj.color = config
-                        .object_colors
-                        .choose(&mut rng)
-                        .copied()
-                        .unwrap_or(Rgba::WHITE);
-                    self.objects.push(obj);
-                }
-            }
+                    ),
+                )
+            };
+            obj.orientation = Rotor4::new(random_quaternion(), random_quaternion());
         }
+        let mut random_angle = || rng.gen_range(-1.0..=1.0);
+        let velocity = Bivector4 {
+            xy: random_angle(),
+            xz: random_angle(),
+            xw: random_angle(),
+            yz: random_angle(),
+            yw: random_angle(),
+            zw: random_angle(),
+        };
+        obj.angular_velocity = velocity * (1.0 / velocity.len().max(1e-3));
+        obj.scale = scale;
+        obj.color = color;
+        obj
+    }
 
-        for obj in &mut self.objects {
-            obj.position += vec4::UNIT_W * config.speed.value() * delta_time;
-            obj.rotate_zw(Angle::from_degrees(
-                config.rotation_speed_degrees.value() * delta_time,
-            ));
-        }
-        // Delete far objects
-        self.objects.retain(|obj| obj.position.w < obj.scale * 2.0);
+    fn drift(&self, object: &mut Object, distance: f32, angle: f32) {
+        object.position += vec4_norm(self.cross_space.normal) * distance;
+        object.rotate(object.angular_velocity * angle);
     }
+}
 
-    pub fn draw(&mut self, config: &Config, framebuffer: &mut ugli::Framebuffer) {
-        ugli::clear(framebuffer, Some(config.background_color), Some(1.0), None);
+/// Draws 3d geometry with outlines, used to display the cross sections.
+pub struct SectionRenderer {
+    geng: Geng,
+    unit_geometry: Rc<ugli::VertexBuffer<draw2d::TexturedVertex>>,
+    screen_texture: ugli::Texture,
+    normal_texture: ugli::Texture,
+    depth_buffer: ugli::Renderbuffer<ugli::DepthComponent>,
+}
 
-        self.framebuffer_size = framebuffer.size();
+impl SectionRenderer {
+    pub fn new(geng: &Geng) -> Self {
+        Self {
+            geng: geng.clone(),
+            unit_geometry: Rc::new(geng_utils::geometry::unit_quad_geometry(geng.ugli())),
+            screen_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
+            normal_texture: texture_utils::new_texture(geng.ugli(), vec2(1, 1)),
+            depth_buffer: ugli::Renderbuffer::new(geng.ugli(), vec2(1, 1)),
+        }
+    }
 
-        if self.screen_texture.size() != self.framebuffer_size {
-            self.depth_buffer = ugli::Renderbuffer::new(self.geng.ugli(), self.framebuffer_size);
+    pub fn draw(
+        &mut self,
+        geometry: &[&ugli::VertexBuffer<Vertex>],
+        camera: &Camera3d,
+        assets: &Assets,
+        background_color: Rgba<f32>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let framebuffer_size = framebuffer.size();
+        if self.screen_texture.size() != framebuffer_size {
+            self.depth_buffer = ugli::Renderbuffer::new(self.geng.ugli(), framebuffer_size);
         }
         texture_utils::update_texture_size(
             &mut self.screen_texture,
-            self.framebuffer_size,
+            framebuffer_size,
             self.geng.ugli(),
         );
         texture_utils::update_texture_size(
             &mut self.normal_texture,
-            self.framebuffer_size,
+            framebuffer_size,
             self.geng.ugli(),
         );
 
-        let cross_space = Space { offset: 0.0 };
-
-        // Calculate a cross section
-        let geometry: Vec<Vertex> = self
-            .objects
-            .iter()
-            .flat_map(|obj| {
-                let matrix = obj.matrix();
-                let transform = |v: vec4<f32>| (matrix * vec5(v.x, v.y, v.z, v.w, 1.0)).into_4d();
-                cross_space
-                    .cross_sect(obj.geometry.iter().map(|tetra| Tetrahedron4d {
-                        vertices: tetra.vertices.map(transform),
-                    }))
-                    .into_iter()
-                    .flat_map(|triangle| triangle.into_vertices().map(|v| v.colored(obj.color)))
-            })
-            .collect();
-        let geometry = ugli::VertexBuffer::new_dynamic(self.geng.ugli(), geometry);
-
         {
             // Draw the cross section in 2d
             let mut screen_buffer = ugli::Framebuffer::new(
@@ -222,19 +676,12 @@ impl State3d {
                 ugli::ColorAttachment::Texture(&mut self.screen_texture),
                 ugli::DepthAttachment::Renderbuffer(&mut self.depth_buffer),
             );
-            ugli::clear(
-                &mut screen_buffer,
-                Some(config.background_color),
-                Some(1.0),
-                None,
-            );
+            ugli::clear(&mut screen_buffer, Some(background_color), Some(1.0), None);
 
-            draw_with(
-                &geometry,
-                &self.camera,
-                &self.assets.simple3d.get(),
-                &mut screen_buffer,
-            );
+            let program = assets.simple3d.get();
+            for geometry in geometry {
+                draw_with(geometry, camera, &program, &mut screen_buffer);
+            }
         }
 
         {
@@ -244,25 +691,18 @@ impl State3d {
                 ugli::ColorAttachment::Texture(&mut self.normal_texture),
                 ugli::DepthAttachment::Renderbuffer(&mut self.depth_buffer),
             );
-            ugli::clear(
-                &mut normal_buffer,
-                Some(config.background_color),
-                Some(1.0),
-                None,
-            );
+            ugli::clear(&mut normal_buffer, Some(background_color), Some(1.0), None);
 
-            draw_with(
-                &geometry,
-                &self.camera,
-                &self.assets.outline_marker.get(),
-                &mut normal_buffer,
-            );
+            let program = assets.outline_marker.get();
+            for geometry in geometry {
+                draw_with(geometry, camera, &program, &mut normal_buffer);
+            }
         }
 
         // Postprocess
         ugli::draw(
             framebuffer,
-            &self.assets.postprocess.get(),
+            &assets.postprocess.get(),
             ugli::DrawMode::TriangleFan,
             &*self.unit_geometry,
             ugli::uniforms! {
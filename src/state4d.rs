@@ -0,0 +1,163 @@
+use crate::{
+    camera3d::Camera3d,
+    geometry::{
+        hyperplane::{Hyperplane, SimplexMesh},
+        mat5,
+        shape::Mesh4d,
+        space::Space,
+        MatN, VecN, Vertex,
+    },
+    mode::{draw_prefab_ui, Pointer, PointerAction},
+    state3d::SectionRenderer,
+    Assets, Config,
+};
+
+use geng::prelude::*;
+
+/// Planes of the axes the object spins in, with the relative angular speeds.
+const SPIN_PLANES: [(usize, usize, f32); 3] = [(0, 4, 1.0), (1, 3, 0.6), (2, 4, 0.3)];
+
+/// Only one prefab is active at a time, it is the displayed polytope.
+pub type Prefab = crate::mode::Prefab<Rc<SimplexMesh<5>>>;
+
+/// Shows a 5d polytope by slicing it with a 4d hyperplane,
+/// and then slicing the resulting 4d section again with a 3d space.
+pub struct State4d {
+    geng: Geng,
+    assets: Rc<Assets>,
+    renderer: SectionRenderer,
+    camera: Camera3d,
+    prefabs: Vec<Prefab>,
+    /// Current rotation angles in the [SPIN_PLANES].
+    angles: [f32; 3],
+    pointer: Pointer,
+}
+
+impl State4d {
+    pub fn new(geng: Geng, assets: Rc<Assets>) -> Self {
+        let prefab = |name: &str, active: bool, mesh: SimplexMesh<5>| {
+            Prefab::new(name, active, Rc::new(mesh))
+        };
+        Self {
+            renderer: SectionRenderer::new(&geng),
+            camera: Camera3d {
+                fov: Angle::from_radians(70.0),
+                pos: vec3(0.0, 0.0, 6.0),
+                rot_h: Angle::ZERO,
+                rot_v: Angle::ZERO,
+                near: 1.0,
+                far: 50.0,
+            },
+            prefabs: vec![
+                prefab("5-simplex", true, crate::geometry::shape::unit_5simplex()),
+                prefab("5-cube", false, crate::geometry::shape::unit_5cube()),
+                prefab(
+                    "5-orthoplex",
+                    false,
+                    crate::geometry::shape::unit_5orthoplex(),
+                ),
+            ],
+            angles: [0.0; 3],
+            pointer: Pointer::default(),
+            geng,
+            assets,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &geng::Event) {
+        if self.pointer.handle_event(event) != Some(PointerAction::Click) {
+            return;
+        }
+        let pos = self.pointer.cursor_pos;
+        if self
+            .prefabs
+            .iter()
+            .any(|prefab| prefab.ui_checkbox.contains(pos))
+        {
+            for prefab in &mut self.prefabs {
+                prefab.set_active(prefab.ui_checkbox.contains(pos));
+            }
+        }
+    }
+
+    pub fn update(&mut self, config: &Config, delta_time: f64) {
+        let delta_time = delta_time as f32;
+        let speed = config.rotation_speed_degrees.value().to_radians();
+        for (angle, &(_, _, factor)) in self.angles.iter_mut().zip(&SPIN_PLANES) {
+            *angle += speed * factor * delta_time;
+        }
+    }
+
+    pub fn draw(&mut self, config: &Config, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(config.background_color), Some(1.0), None);
+
+        // Rotate the polytope in 5d
+        let rotation = SPIN_PLANES
+            .iter()
+            .zip(self.angles)
+            .fold(MatN::identity(), |acc, (&(a, b, _), angle)| {
+                MatN::rotate(a, b, Angle::from_radians(angle)) * acc
+            });
+        let Some(polytope) = self
+            .prefabs
+            .iter()
+            .find(|prefab| prefab.is_active())
+            .and_then(Prefab::shape)
+        else {
+            return;
+        };
+        let mesh = SimplexMesh {
+            vertices: polytope.vertices.iter().map(|&v| rotation * v).collect(),
+            simplices: polytope.simplices.clone(),
+        };
+
+        // 5d -> 4d
+        let hyperplane = Hyperplane {
+            normal: VecN::unit(4),
+            offset: config.offset_5d.value(),
+        };
+        let section = hyperplane.slice(&mesh);
+        let project = hyperplane.local_projection::<4>();
+        let section = Mesh4d::new(
+            section
+                .vertices
+                .iter()
+                .map(|&v| project(v).into())
+                .collect(),
+            section
+                .simplices
+                .iter()
+                .map(|simplex| [simplex[0], simplex[1], simplex[2], simplex[3]])
+                .collect(),
+        );
+
+        // 4d -> 3d
+        let space = Space {
+            normal: vec4::UNIT_W,
+            offset: config.offset_4d.value(),
+        };
+        let color = config.object_colors.first().copied().unwrap_or(Rgba::WHITE);
+        let geometry: Vec<Vertex> = space
+            .cross_sect(&section, mat5::identity())
+            .to_vertices()
+            .into_iter()
+            .map(|v| v.colored(color))
+            .collect();
+        let geometry = ugli::VertexBuffer::new_dynamic(self.geng.ugli(), geometry);
+
+        self.renderer.draw(
+            &[&geometry],
+            &self.camera,
+            &self.assets,
+            config.background_color,
+            framebuffer,
+        );
+
+        draw_prefab_ui(
+            &mut self.prefabs,
+            self.pointer.cursor_pos,
+            &self.geng,
+            framebuffer,
+        );
+    }
+}